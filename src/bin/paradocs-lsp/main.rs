@@ -0,0 +1,304 @@
+mod rpc;
+
+use std::collections::HashMap;
+use std::io;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use paradocs::blocking::DocsClient;
+use paradocs::element::ItemKind;
+use paradocs::render::{to_embed, EmbedLimits, EmbedOptions, Sections};
+use paradocs::ItemIndex;
+use rpc::{RpcError, INVALID_PARAMS, METHOD_NOT_FOUND, PARSE_ERROR};
+use serde_json::{json, Value};
+
+/// Hovers show the declaration and the first paragraphs of the description.
+const HOVER_LIMITS: EmbedLimits = EmbedLimits {
+    title: 256,
+    description: 2000,
+    field_name: 0,
+    field_value: 0,
+    fields: 0,
+    total: 2256,
+};
+
+/// How long a crate which could not be listed is left alone before it is tried again.
+const INDEX_RETRY: Duration = Duration::from_secs(60);
+
+/// Answers hovers over and completes Rust paths, such as `tokio::net::TcpStream`,
+/// in any kind of file.
+struct Server {
+    client: DocsClient,
+    documents: HashMap<String, String>,
+    /// Item indexes by crate, or when to try again for crates which could not be listed.
+    indexes: HashMap<String, Result<ItemIndex, Instant>>,
+    shutdown: bool,
+}
+
+/// A range of a line, in the UTF-16 code units LSP counts positions in.
+fn range(line: u64, text: &str, start: usize, end: usize) -> Value {
+    let column = |offset: usize| text[..offset].encode_utf16().count();
+    json!({
+        "start": { "line": line, "character": column(start) },
+        "end": { "line": line, "character": column(end) },
+    })
+}
+
+fn is_path_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == ':'
+}
+
+/// The kinds of completion items defined by LSP.
+fn completion_kind(kind: ItemKind) -> u32 {
+    match kind {
+        ItemKind::Module => 9,
+        ItemKind::Struct => 22,
+        ItemKind::Enum => 13,
+        ItemKind::Constant => 21,
+        ItemKind::Function | ItemKind::Macro | ItemKind::Attribute => 3,
+        ItemKind::Trait => 8,
+        ItemKind::Type | ItemKind::Primitive => 25,
+        ItemKind::Keyword => 14,
+    }
+}
+
+impl Server {
+    fn new(client: DocsClient) -> Self {
+        Server {
+            client,
+            documents: HashMap::new(),
+            indexes: HashMap::new(),
+            shutdown: false,
+        }
+    }
+
+    fn handle(&mut self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "hoverProvider": true,
+                    "completionProvider": { "triggerCharacters": [":"] },
+                },
+                "serverInfo": { "name": "paradocs-lsp", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                if let (Some(uri), Some(text)) = (document["uri"].as_str(), document["text"].as_str()) {
+                    self.documents.insert(uri.to_owned(), text.to_owned());
+                }
+                Ok(Value::Null)
+            }
+            "textDocument/didChange" => {
+                // only whole documents are synced, so the last change has the full text
+                let uri = params["textDocument"]["uri"].as_str();
+                let text = params["contentChanges"].as_array().and_then(|changes| changes.last()?["text"].as_str());
+                if let (Some(uri), Some(text)) = (uri, text) {
+                    self.documents.insert(uri.to_owned(), text.to_owned());
+                }
+                Ok(Value::Null)
+            }
+            "textDocument/didClose" => {
+                if let Some(uri) = params["textDocument"]["uri"].as_str() {
+                    self.documents.remove(uri);
+                }
+                Ok(Value::Null)
+            }
+            "textDocument/hover" => self.hover(params),
+            "textDocument/completion" => self.completion(params),
+            _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("unsupported method `{}`", method))),
+        }
+    }
+
+    /// The line at the position of a request, and the byte offset of the position in it.
+    fn locate(&self, params: &Value) -> Result<(u64, &str, usize), RpcError> {
+        let invalid = || RpcError::new(INVALID_PARAMS, "expected a text document and a position");
+        let uri = params["textDocument"]["uri"].as_str().ok_or_else(invalid)?;
+        let line_number = params["position"]["line"].as_u64().ok_or_else(invalid)?;
+        let character = params["position"]["character"].as_u64().ok_or_else(invalid)? as usize;
+        let text = self
+            .documents
+            .get(uri)
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("`{}` is not open", uri)))?;
+        let line = text.lines().nth(line_number as usize).unwrap_or_default();
+        let mut units = 0;
+        for (offset, c) in line.char_indices() {
+            if units >= character {
+                return Ok((line_number, line, offset));
+            }
+            units += c.len_utf16();
+        }
+        Ok((line_number, line, line.len()))
+    }
+
+    /// Shows the document of the path under the cursor, up to the segment the cursor is on.
+    fn hover(&mut self, params: &Value) -> Result<Value, RpcError> {
+        let (line_number, line, offset) = self.locate(params)?;
+        let start = line[..offset]
+            .char_indices()
+            .rev()
+            .find(|(_, c)| !is_path_char(*c))
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let end = line[offset..].find(|c| !is_path_char(c)).map_or(line.len(), |i| offset + i);
+        let token = &line[start..end];
+        let token_start = start + (token.len() - token.trim_start_matches(':').len());
+        let segment_end = line[offset..end].find("::").map_or(end, |i| offset + i);
+        let path = line[token_start..segment_end].trim_end_matches(':');
+        // a lone word is rarely meant as a crate
+        if !line[token_start..end].contains("::") {
+            return Ok(Value::Null);
+        }
+
+        let (url, document) = match self.client.get_document_page(path, "*") {
+            Some(page) => page,
+            None => return Ok(Value::Null),
+        };
+        // relative links start from the directory of the page
+        let directory = &url[..url.rfind('/').map_or(0, |i| i + 1)];
+        let options = EmbedOptions::new()
+            .limits(HOVER_LIMITS)
+            .sections(Sections {
+                description: true,
+                ..Sections::none()
+            })
+            .link_base(directory);
+        let embed = to_embed(&document, &options);
+        Ok(json!({
+            "contents": {
+                "kind": "markdown",
+                "value": format!("**{}**\n\n{}", embed.title(), embed.description()),
+            },
+            "range": range(line_number, line, token_start, token_start + path.len()),
+        }))
+    }
+
+    fn index(&mut self, crate_name: &str) -> Option<&ItemIndex> {
+        let stale = match self.indexes.get(crate_name) {
+            Some(Ok(_)) => false,
+            Some(Err(retry)) => Instant::now() >= *retry,
+            None => true,
+        };
+        if stale {
+            let index = self.client.item_index(crate_name).map_err(|_| Instant::now() + INDEX_RETRY);
+            self.indexes.insert(crate_name.to_owned(), index);
+        }
+        self.indexes.get(crate_name)?.as_ref().ok()
+    }
+
+    /// Offers the items of the module before the cursor, such as those of
+    /// `tokio::net` after typing `tokio::net::`.
+    fn completion(&mut self, params: &Value) -> Result<Value, RpcError> {
+        let (line_number, line, offset) = self.locate(params)?;
+        let before = &line[..offset];
+        let start = before
+            .char_indices()
+            .rev()
+            .find(|(_, c)| !is_path_char(*c))
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let typed = before[start..].trim_start_matches(':');
+        let (parent, partial) = match typed.rsplit_once("::") {
+            Some(split) => split,
+            None => return Ok(json!({ "isIncomplete": false, "items": [] })),
+        };
+        let crate_name = parent.split("::").next().unwrap_or_default().to_owned();
+        let edit_range = range(line_number, line, offset - partial.len(), offset);
+
+        let prefix = format!("{}::", parent);
+        let mut children: Vec<(&str, ItemKind)> = Vec::new();
+        if let Some(index) = self.index(&crate_name) {
+            for entry in index.entries() {
+                let rest = match entry.path().strip_prefix(&prefix) {
+                    Some(rest) => rest,
+                    None => continue,
+                };
+                // items further down mean the segment is a module, even if it is not listed itself
+                let (segment, kind) = match rest.split_once("::") {
+                    Some((segment, _)) => (segment, ItemKind::Module),
+                    None => (rest, entry.kind()),
+                };
+                if !children.iter().any(|(name, _)| *name == segment) {
+                    children.push((segment, kind));
+                }
+            }
+        }
+        children.sort_by_key(|(name, _)| *name);
+        let items: Vec<Value> = children
+            .into_iter()
+            .map(|(name, kind)| {
+                json!({
+                    "label": name,
+                    "kind": completion_kind(kind),
+                    "detail": format!("{}{}", prefix, name),
+                    "textEdit": { "range": edit_range, "newText": name },
+                })
+            })
+            .collect();
+        Ok(json!({ "isIncomplete": false, "items": items }))
+    }
+}
+
+/// Talks to docs.rs, or records to / replays from the directory in
+/// `PARADOCS_RECORD` / `PARADOCS_REPLAY`.
+fn client() -> paradocs::Result<DocsClient> {
+    let builder = DocsClient::builder();
+    let builder = if let Some(directory) = std::env::var_os("PARADOCS_RECORD") {
+        builder.record(directory)
+    } else if let Some(directory) = std::env::var_os("PARADOCS_REPLAY") {
+        builder.replay(directory)
+    } else {
+        builder
+    };
+    builder.build_blocking()
+}
+
+fn main() -> ExitCode {
+    let mut server = match client() {
+        Ok(client) => Server::new(client),
+        Err(e) => {
+            eprintln!("paradocs-lsp: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let stdout = io::stdout();
+    let mut output = stdout.lock();
+    loop {
+        let body = match rpc::read_message(&mut input) {
+            Ok(Some(body)) => body,
+            Ok(None) => return ExitCode::FAILURE,
+            Err(e) => {
+                eprintln!("paradocs-lsp: {}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        let message: Value = match serde_json::from_slice(&body) {
+            Ok(message) => message,
+            Err(e) => {
+                let response = rpc::response(Value::Null, Err(RpcError::new(PARSE_ERROR, e.to_string())));
+                if rpc::write_message(&mut output, &response).is_err() {
+                    return ExitCode::FAILURE;
+                }
+                continue;
+            }
+        };
+        // responses to requests of our own; none are sent
+        let method = match message["method"].as_str() {
+            Some(method) => method,
+            None => continue,
+        };
+        if method == "exit" {
+            return if server.shutdown { ExitCode::SUCCESS } else { ExitCode::FAILURE };
+        }
+        let result = server.handle(method, &message["params"]);
+        // notifications have no id and get no response
+        if let Some(id) = message.get("id") {
+            if rpc::write_message(&mut output, &rpc::response(id.clone(), result)).is_err() {
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use std::convert::Infallible;
+use std::io::{self, Write};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+use clap::Parser;
+use hyper::header::CONTENT_TYPE;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use paradocs::element::{Declared, Document, DocumentKind, Exportable, ItemContainer};
+use paradocs::{DiskCache, DocsClient, MemoryCache};
+use reqwest::Url;
+use serde_json::{json, Value};
+
+/// Serves documentation from docs.rs as JSON.
+///
+/// Endpoints, where `version` may be `latest`:
+///
+///   GET /crates/{name}/{version}/items/{path}    the document of an item
+///   GET /crates/{name}/{version}/modules/{path}  the items of a module
+///   GET /crates/{name}/{version}/search?q=...    items matching a query
+///
+/// Items docs.rs does not have are answered with 404, and failures to reach
+/// docs.rs with 502, or 504 once `--timeout` runs out.
+#[derive(Parser)]
+#[command(name = "paradocs-server", verbatim_doc_comment)]
+struct Args {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    bind: SocketAddr,
+    /// Directory to cache pages in across restarts
+    #[arg(long, value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
+    /// Number of parsed documents to keep in memory
+    #[arg(long, value_name = "DOCUMENTS", default_value_t = 1024)]
+    memory_cache: usize,
+    /// docs.rs, or a mirror of it, to read documentation from
+    #[arg(long, value_name = "URL", default_value = "https://docs.rs")]
+    base_url: String,
+    /// Seconds to wait for a page before answering 504 Gateway Timeout
+    #[arg(long, value_name = "SECONDS", default_value = "30", value_parser = parse_seconds)]
+    timeout: Duration,
+}
+
+fn parse_seconds(value: &str) -> Result<Duration, String> {
+    value
+        .parse()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("`{}` is not a number of seconds", value))
+}
+
+/// A failed request, answered with `{"error": message}`.
+struct ApiError(StatusCode, String);
+
+impl ApiError {
+    fn not_found(message: impl Into<String>) -> Self {
+        ApiError(StatusCode::NOT_FOUND, message.into())
+    }
+}
+
+impl From<paradocs::Error> for ApiError {
+    fn from(e: paradocs::Error) -> Self {
+        match e {
+            paradocs::Error::PackageNotFound(_)
+            | paradocs::Error::ItemNotFound(_)
+            | paradocs::Error::Status { status: 404, .. } => ApiError(StatusCode::NOT_FOUND, e.to_string()),
+            paradocs::Error::Request(ref error) if error.is_timeout() => ApiError(StatusCode::GATEWAY_TIMEOUT, e.to_string()),
+            e => ApiError(StatusCode::BAD_GATEWAY, e.to_string()),
+        }
+    }
+}
+
+/// Talks to docs.rs, or records to / replays from the directory in
+/// `PARADOCS_RECORD` / `PARADOCS_REPLAY`.
+fn client(args: &Args) -> Result<DocsClient, Box<dyn std::error::Error>> {
+    let mut builder = DocsClient::builder()
+        .base_url(args.base_url.as_str())
+        .timeout(args.timeout)
+        .memory_cache(MemoryCache::new(args.memory_cache));
+    if let Some(directory) = &args.cache_dir {
+        builder = builder.disk_cache(DiskCache::new(directory)?);
+    }
+    if let Some(directory) = std::env::var_os("PARADOCS_RECORD") {
+        builder = builder.record(directory);
+    } else if let Some(directory) = std::env::var_os("PARADOCS_REPLAY") {
+        builder = builder.replay(directory);
+    }
+    Ok(builder.build()?)
+}
+
+/// docs.rs also understands `latest`, but `*` is what the client caches latest pages under.
+fn version(version: &str) -> &str {
+    if version == "latest" {
+        "*"
+    } else {
+        version
+    }
+}
+
+/// Joins the crate name and the rest of the URL path, in which `/` and `::` both separate segments.
+fn item_path(crate_name: &str, path: &[String]) -> String {
+    let mut segments = vec![crate_name];
+    segments.extend(path.iter().flat_map(|segment| segment.split("::")).filter(|segment| !segment.is_empty()));
+    segments.join("::")
+}
+
+/// Looks up the document at `path`, answering 404 only when docs.rs does not have it.
+async fn document(client: &DocsClient, path: &str, version: &str) -> Result<Arc<Document>, ApiError> {
+    client.try_get_document_version(path, version).await.map_err(|e| match e {
+        paradocs::Error::PackageNotFound(_) | paradocs::Error::ItemNotFound(_) => {
+            ApiError::not_found(format!("no documentation found for `{}`", path))
+        }
+        e => e.into(),
+    })
+}
+
+async fn item(client: &DocsClient, name: &str, version: &str, path: &[String]) -> Result<Value, ApiError> {
+    let path = item_path(name, path);
+    let document = document(client, &path, version).await?;
+    Ok(serde_json::to_value(&*document).expect("documents serialize to JSON"))
+}
+
+async fn module(client: &DocsClient, name: &str, version: &str, path: &[String]) -> Result<Value, ApiError> {
+    let path = item_path(name, path);
+    let document = document(client, &path, version).await?;
+    let module = match document.kind() {
+        DocumentKind::Crate(module) | DocumentKind::Module(module) => module,
+        _ => return Err(ApiError::not_found(format!("`{}` is not a module", path))),
+    };
+    let parent = &path;
+    let items: Vec<Value> = module
+        .sub_item()
+        .iter()
+        .flat_map(|section| {
+            section.content().iter().map(move |item| {
+                json!({
+                    "name": item.name(),
+                    "path": format!("{}::{}", parent, item.name()),
+                    "kind": section.item_type(),
+                    "summary": item.summary().to_string().split_whitespace().collect::<Vec<_>>().join(" "),
+                })
+            })
+        })
+        .collect();
+    let re_exports: Vec<&str> = module.re_exports().iter().map(|export| export.declaration().as_str()).collect();
+    Ok(json!({
+        "path": path,
+        "re_exports": re_exports,
+        "items": items,
+    }))
+}
+
+async fn search(client: &DocsClient, name: &str, version: &str, query: &str) -> Result<Value, ApiError> {
+    let index = client.item_index_version(name, version).await?;
+    let entries: Vec<Value> = index
+        .search(query)
+        .into_iter()
+        .map(|entry| json!({ "path": entry.path(), "kind": entry.kind(), "url": entry.url() }))
+        .collect();
+    Ok(Value::Array(entries))
+}
+
+async fn route(client: &DocsClient, request: &Request<Body>) -> Result<Value, ApiError> {
+    if request.method() != Method::GET {
+        return Err(ApiError(StatusCode::METHOD_NOT_ALLOWED, "only GET is supported".to_owned()));
+    }
+    let url = Url::parse(&format!("http://localhost{}", request.uri()))
+        .map_err(|_| ApiError(StatusCode::BAD_REQUEST, "invalid URL".to_owned()))?;
+    let segments: Vec<String> = url
+        .path_segments()
+        .into_iter()
+        .flatten()
+        .filter(|segment| !segment.is_empty())
+        .map(percent_decode)
+        .collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    match segments.as_slice() {
+        ["crates", name, version_name, rest @ ..] => {
+            let version = version(version_name);
+            let path: Vec<String> = rest.iter().skip(1).map(|segment| segment.to_string()).collect();
+            match rest.first() {
+                None => item(client, name, version, &[]).await,
+                Some(&"items") => item(client, name, version, &path).await,
+                Some(&"modules") => module(client, name, version, &path).await,
+                Some(&"search") if rest.len() == 1 => {
+                    let query = url
+                        .query_pairs()
+                        .find(|(key, _)| key == "q")
+                        .map(|(_, value)| value.into_owned())
+                        .ok_or_else(|| ApiError(StatusCode::BAD_REQUEST, "missing query parameter `q`".to_owned()))?;
+                    search(client, name, version, &query).await
+                }
+                _ => Err(ApiError::not_found("no such endpoint")),
+            }
+        }
+        _ => Err(ApiError::not_found("no such endpoint")),
+    }
+}
+
+async fn handle(client: Arc<DocsClient>, request: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let (status, body) = match route(&client, &request).await {
+        Ok(value) => (StatusCode::OK, value),
+        Err(ApiError(status, message)) => (status, json!({ "error": message })),
+    };
+    let body = serde_json::to_vec(&body).expect("JSON values serialize");
+    Ok(Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .expect("responses are valid"))
+}
+
+async fn serve(address: SocketAddr, client: DocsClient) -> Result<(), hyper::Error> {
+    let client = Arc::new(client);
+    let service = make_service_fn(move |_| {
+        let client = client.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |request| handle(client.clone(), request))) }
+    });
+    let server = Server::try_bind(&address)?.serve(service);
+    // printed for whoever started the server on port 0
+    println!("listening on http://{}", server.local_addr());
+    let _ = io::stdout().flush();
+    server.await
+}
+
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|hex| std::str::from_utf8(hex).ok());
+        match hex.filter(|_| bytes[i] == b'%').and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let client = match client(&args) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("paradocs-server: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let runtime = tokio::runtime::Builder::new().threaded_scheduler().enable_all().build();
+    let result = match runtime {
+        Ok(mut runtime) => runtime.block_on(serve(args.bind, client)).map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("paradocs-server: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
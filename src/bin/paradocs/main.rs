@@ -0,0 +1,228 @@
+mod render;
+#[cfg(feature = "tui")]
+mod tui;
+
+use clap::{ArgGroup, Parser, ValueEnum};
+use paradocs::blocking::DocsClient;
+use paradocs::render::{symbols, to_ctags, to_man, to_symbol_json, write_man_pages, ManOptions, Sections};
+use paradocs::CrawlOptions;
+use render::Renderer;
+use reqwest::Url;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Looks up Rust documentation on docs.rs.
+#[derive(Parser)]
+#[command(name = "paradocs", disable_version_flag = true)]
+#[command(group(ArgGroup::new("sections").multiple(true)))]
+struct Args {
+    /// Item to look up, such as `tokio::net::TcpStream`
+    path: String,
+    /// Version of the crate, such as `0.2` or `0.2.21`
+    #[arg(long)]
+    version: Option<String>,
+    /// Print the document as JSON
+    #[arg(long, conflicts_with = "sections")]
+    json: bool,
+    /// Print the document as a man page
+    #[arg(long, conflicts_with = "json")]
+    man: bool,
+    /// Write man pages for every item of the crate to DIR/man3
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["json", "man", "version"])]
+    man_dir: Option<PathBuf>,
+    /// Print a tags file for every item of the crate
+    #[arg(long, conflicts_with_all = ["sections", "json", "man", "man_dir", "version"])]
+    tags: bool,
+    /// Print a JSON symbol table for every item of the crate
+    #[arg(long, conflicts_with_all = ["sections", "json", "man", "man_dir", "tags", "version"])]
+    symbols: bool,
+    /// Write the crate as a Dash/Zeal docset in DIR
+    #[cfg(feature = "docset")]
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["json", "man", "man_dir", "tags", "symbols", "version"])]
+    docset: Option<PathBuf>,
+    /// Browse the crate in a full-screen viewer
+    #[cfg(feature = "tui")]
+    #[arg(long, short, conflicts_with_all = ["sections", "json", "man", "man_dir", "tags", "symbols"])]
+    browse: bool,
+    /// When to use colors and hyperlinks
+    #[arg(long, value_enum, default_value_t = Color::Auto)]
+    color: Color,
+    /// Only show the description
+    #[arg(long, group = "sections")]
+    description: bool,
+    /// Only show the examples
+    #[arg(long, group = "sections")]
+    examples: bool,
+    /// Only show the methods
+    #[arg(long, group = "sections")]
+    methods: bool,
+    /// Only show the fields of a struct
+    #[arg(long, group = "sections")]
+    fields: bool,
+    /// Only show the variants of an enum
+    #[arg(long, group = "sections")]
+    variants: bool,
+    /// Only show trait implementations and implementors
+    #[arg(long, group = "sections")]
+    impls: bool,
+    /// Only show the items of a crate or module
+    #[arg(long, group = "sections")]
+    items: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Color {
+    Auto,
+    Always,
+    Never,
+}
+
+impl Args {
+    fn sections(&self) -> Sections {
+        let sections = Sections {
+            description: self.description,
+            examples: self.examples,
+            methods: self.methods,
+            fields: self.fields,
+            variants: self.variants,
+            impls: self.impls,
+            items: self.items,
+        };
+        if sections.is_empty() {
+            Sections::all()
+        } else {
+            sections
+        }
+    }
+
+    fn color(&self) -> bool {
+        match self.color {
+            Color::Always => true,
+            Color::Never => false,
+            Color::Auto => io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+        }
+    }
+}
+
+/// Talks to docs.rs, or records to / replays from the directory in
+/// `PARADOCS_RECORD` / `PARADOCS_REPLAY`.
+fn client() -> paradocs::Result<DocsClient> {
+    let builder = DocsClient::builder();
+    let builder = if let Some(directory) = std::env::var_os("PARADOCS_RECORD") {
+        builder.record(directory)
+    } else if let Some(directory) = std::env::var_os("PARADOCS_REPLAY") {
+        builder.replay(directory)
+    } else {
+        builder
+    };
+    builder.build_blocking()
+}
+
+/// Crawls the crate of `args.path` and writes its man pages, listing the files written.
+fn man_dir(client: &DocsClient, args: &Args, directory: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let crate_name = args.path.split("::").next().unwrap_or_default();
+    let index = client.crawl(crate_name, &CrawlOptions::new())?;
+    let files = write_man_pages(&index, directory, &ManOptions::new().sections(args.sections()))?;
+    let mut stdout = io::stdout();
+    for file in files {
+        let _ = writeln!(stdout, "{}", file.display());
+    }
+    Ok(())
+}
+
+/// Crawls the crate of `args.path` and writes it as a docset, printing its path.
+#[cfg(feature = "docset")]
+fn docset(client: &DocsClient, args: &Args, directory: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    use paradocs::render::{write_docset, DocsetOptions};
+
+    let crate_name = args.path.split("::").next().unwrap_or_default();
+    let index = client.crawl(crate_name, &CrawlOptions::new())?;
+    let docset = write_docset(&index, directory, &DocsetOptions::new().sections(args.sections()))?;
+    let _ = writeln!(io::stdout(), "{}", docset.display());
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let client = match client() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("paradocs: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    #[cfg(feature = "tui")]
+    if args.browse {
+        return match tui::run(client, &args.path, args.version.as_deref()) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("paradocs: {}", e);
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    if let Some(directory) = &args.man_dir {
+        return match man_dir(&client, &args, directory) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("paradocs: {}", e);
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    if args.tags || args.symbols {
+        let crate_name = args.path.split("::").next().unwrap_or_default();
+        let index = match client.crawl(crate_name, &CrawlOptions::new()) {
+            Ok(index) => index,
+            Err(e) => {
+                eprintln!("paradocs: {}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        let symbols = symbols(&index);
+        let output = if args.tags {
+            to_ctags(&symbols)
+        } else {
+            to_symbol_json(&symbols) + "\n"
+        };
+        let _ = io::stdout().write_all(output.as_bytes());
+        return ExitCode::SUCCESS;
+    }
+
+    #[cfg(feature = "docset")]
+    if let Some(directory) = &args.docset {
+        return match docset(&client, &args, directory) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("paradocs: {}", e);
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    let (url, document) = match client.get_document_page(&args.path, args.version.as_deref().unwrap_or("*")) {
+        Some(page) => page,
+        None => {
+            eprintln!("paradocs: no documentation found for `{}`", args.path);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let output = if args.man {
+        to_man(&document, &ManOptions::new().sections(args.sections()))
+    } else if args.json {
+        let mut json = serde_json::to_string_pretty(&*document).expect("documents serialize to JSON");
+        json.push('\n');
+        json
+    } else {
+        Renderer::new(args.color(), Url::parse(&url).ok()).render(&document, args.sections())
+    };
+
+    // a closed pipe, as in `paradocs tokio | head`, is not an error
+    let _ = io::stdout().write_all(output.as_bytes());
+    ExitCode::SUCCESS
+}
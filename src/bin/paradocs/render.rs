@@ -0,0 +1,240 @@
+use paradocs::element::*;
+use paradocs::render::{kind_heading, walk_sections, ImplSection, SectionWriter, Sections};
+use reqwest::Url;
+use std::borrow::Cow;
+
+const BOLD: &str = "\x1b[1m";
+const ITALIC: &str = "\x1b[3m";
+const CODE: &str = "\x1b[36m";
+const HEADING: &str = "\x1b[1;4m";
+const NOTE: &str = "\x1b[33m";
+const RESET: &str = "\x1b[0m";
+
+/// Removes control characters other than newlines and tabs, so text from docs.rs cannot
+/// smuggle escape sequences into the terminal.
+fn clean(text: &str) -> Cow<'_, str> {
+    if text.chars().any(|c| c.is_control() && c != '\n' && c != '\t') {
+        text.chars().filter(|&c| !c.is_control() || c == '\n' || c == '\t').collect()
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Renders documents for a terminal, with ANSI styles and OSC 8 hyperlinks when `color` is set.
+pub struct Renderer {
+    color: bool,
+    base: Option<Url>,
+    out: String,
+}
+
+impl Renderer {
+    pub fn new(color: bool, base: Option<Url>) -> Self {
+        Renderer {
+            color,
+            base,
+            out: String::new(),
+        }
+    }
+
+    pub fn render(mut self, document: &Document, sections: Sections) -> String {
+        self.styled(BOLD, document.title());
+        self.out.push('\n');
+        self.mark(document.mark(), 0);
+
+        if let Some(declaration) = document.declaration() {
+            self.out.push('\n');
+            self.code(declaration, 0);
+        }
+
+        for section in sections.filter_description(document.description()) {
+            self.out.push('\n');
+            self.section(section, 0, 0);
+        }
+
+        walk_sections(&mut self, document.kind(), sections);
+        self.out
+    }
+
+    fn styled(&mut self, style: &str, text: &str) {
+        let text = &*clean(text);
+        if self.color {
+            self.out.push_str(style);
+            self.out.push_str(text);
+            self.out.push_str(RESET);
+        } else {
+            self.out.push_str(text);
+        }
+    }
+
+    fn heading(&mut self, title: &str) {
+        self.out.push('\n');
+        self.styled(HEADING, title);
+        self.out.push('\n');
+    }
+
+    fn indented(&mut self, indent: usize, text: &str) {
+        for line in text.lines() {
+            self.out.extend(std::iter::repeat_n(' ', indent));
+            self.out.push_str(line);
+            self.out.push('\n');
+        }
+    }
+
+    fn code(&mut self, code: &str, indent: usize) {
+        let code = &*clean(code);
+        let code = if self.color {
+            code.lines().map(|line| format!("{}{}{}", CODE, line, RESET)).collect::<Vec<_>>().join("\n")
+        } else {
+            code.to_owned()
+        };
+        self.indented(indent, &code);
+    }
+
+    fn mark(&mut self, mark: &Mark, indent: usize) {
+        for note in [mark.stability(), mark.deprecated(), mark.features()] {
+            if !note.trim().is_empty() {
+                let note = &*clean(note);
+                let note = if self.color {
+                    format!("{}{}{}", NOTE, note, RESET)
+                } else {
+                    note.to_owned()
+                };
+                self.indented(indent, &note);
+            }
+        }
+    }
+
+    fn text(&self, text: &Text) -> String {
+        let mut out = String::new();
+        for component in text.components() {
+            let text = clean(component.text());
+            if !self.color {
+                out.push_str(&text);
+                continue;
+            }
+            let link = component
+                .link()
+                .and_then(|link| match &self.base {
+                    Some(base) => base.join(link).ok(),
+                    None => Url::parse(link).ok(),
+                });
+            if let Some(link) = &link {
+                out.push_str(&format!("\x1b]8;;{}\x1b\\", link));
+            }
+            let mut style = String::new();
+            if component.is_bold() {
+                style.push_str(BOLD);
+            }
+            if component.is_italic() {
+                style.push_str(ITALIC);
+            }
+            if component.is_code() {
+                style.push_str(CODE);
+            }
+            if style.is_empty() {
+                out.push_str(&text);
+            } else {
+                out.push_str(&style);
+                out.push_str(&text);
+                out.push_str(RESET);
+            }
+            if link.is_some() {
+                out.push_str("\x1b]8;;\x1b\\");
+            }
+        }
+        out
+    }
+
+    fn description(&mut self, description: &[Section], indent: usize) {
+        for section in description {
+            self.out.push('\n');
+            self.section(section, indent, 0);
+        }
+    }
+
+    fn section(&mut self, section: &Section, indent: usize, depth: usize) {
+        if let Some(title) = section.title() {
+            let title = format!("{} {}", "#".repeat(depth + 1), clean(&title.to_string()));
+            let title = if self.color {
+                format!("{}{}{}", BOLD, title, RESET)
+            } else {
+                title
+            };
+            self.indented(indent, &title);
+        }
+        for (i, paragraph) in section.content().iter().enumerate() {
+            if i > 0 || section.title().is_some() {
+                self.out.push('\n');
+            }
+            match paragraph {
+                Paragraph::Text(text) => {
+                    let text = self.text(text);
+                    self.indented(indent, &text);
+                }
+                Paragraph::List(items) => {
+                    for item in items {
+                        let item = format!("- {}", self.text(item));
+                        self.indented(indent, &item);
+                    }
+                }
+                Paragraph::Code(code) | Paragraph::InvalidCode(code) => self.code(code, indent + 4),
+                Paragraph::SubSection(section) => self.section(section, indent, depth + 1),
+            }
+        }
+    }
+}
+
+impl SectionWriter for Renderer {
+    fn module(&mut self, module: &Module) {
+        if !module.re_exports().is_empty() {
+            self.heading("Re-exports");
+            for export in module.re_exports() {
+                self.code(export.declaration(), 4);
+            }
+        }
+        for summary in module.sub_item() {
+            self.heading(kind_heading(summary.item_type()));
+            for item in summary.content() {
+                let item_name = clean(item.name());
+                let name = if self.color {
+                    let link = item.link().and_then(|link| self.base.as_ref()?.join(link).ok());
+                    match link {
+                        Some(link) => format!("\x1b]8;;{}\x1b\\{}{}{}\x1b]8;;\x1b\\", link, BOLD, item_name, RESET),
+                        None => format!("{}{}{}", BOLD, item_name, RESET),
+                    }
+                } else {
+                    item_name.into_owned()
+                };
+                let summary = self.text(item.summary());
+                if summary.is_empty() {
+                    self.indented(4, &name);
+                } else {
+                    self.indented(4, &format!("{}  {}", name, summary));
+                }
+            }
+        }
+    }
+
+    fn simple_items(&mut self, title: &str, items: &[SimpleItem]) {
+        self.heading(title);
+        for item in items {
+            self.code(item.declaration(), 4);
+            self.mark(item.mark(), 8);
+            self.description(item.description(), 8);
+        }
+    }
+
+    fn implementations(&mut self, section: ImplSection, implementations: &[Implementation]) {
+        self.heading(section.title());
+        for implementation in implementations {
+            self.code(implementation.impl_code(), 4);
+            if section.lists_items() {
+                for item in implementation.associated_types().iter().chain(implementation.methods()) {
+                    self.code(item.declaration(), 8);
+                    self.mark(item.mark(), 12);
+                    self.description(item.description(), 12);
+                }
+            }
+        }
+    }
+}
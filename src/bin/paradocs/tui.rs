@@ -0,0 +1,585 @@
+//! Full-screen browser, opened with `--browse`.
+
+use paradocs::blocking::DocsClient;
+use paradocs::element::*;
+use paradocs::render::{kind_heading, walk_sections, ImplSection, SectionWriter, Sections};
+use paradocs::{IndexEntry, ItemIndex};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, List, ListItem, ListState, Paragraph as TextView};
+use ratatui::{DefaultTerminal, Frame};
+use reqwest::Url;
+use std::sync::Arc;
+
+const HELP: &str = "q quit  / search  tab next link  enter open  ← back  → forward";
+
+struct Link {
+    url: Url,
+    /// Line and span index of every word of the link.
+    spans: Vec<(usize, usize)>,
+}
+
+struct Page {
+    url: Url,
+    document: Arc<Document>,
+    width: usize,
+    lines: Vec<Line<'static>>,
+    links: Vec<Link>,
+    scroll: usize,
+    selected: Option<usize>,
+}
+
+struct Search {
+    query: String,
+    results: Vec<IndexEntry>,
+    state: ListState,
+}
+
+struct App {
+    client: DocsClient,
+    index: Option<ItemIndex>,
+    history: Vec<Page>,
+    current: usize,
+    search: Option<Search>,
+    status: String,
+    height: usize,
+}
+
+/// Browses the docs of the crate which `path` belongs to, starting at `path`.
+///
+/// Search is unavailable when the items of the crate cannot be listed.
+pub fn run(client: DocsClient, path: &str, version: Option<&str>) -> Result<(), String> {
+    let crate_name = path.split("::").next().unwrap_or(path);
+    let index = match version {
+        Some(version) => client.item_index_version(crate_name, version),
+        None => client.item_index(crate_name),
+    }
+    .ok();
+    let (url, document) = match &index {
+        Some(index) => {
+            let url = start_url(index, path).ok_or_else(|| format!("no documentation found for `{}`", path))?;
+            load(&client, url.as_str()).map_err(|e| format!("cannot load {}: {}", url, e))?
+        }
+        None => {
+            let (url, document) = client
+                .get_document_page(path, version.unwrap_or("*"))
+                .ok_or_else(|| format!("no documentation found for `{}`", path))?;
+            let url = Url::parse(&url).map_err(|_| format!("cannot load {}", url))?;
+            (url, document)
+        }
+    };
+
+    let mut app = App {
+        client,
+        index,
+        history: vec![Page::new(url, document)],
+        current: 0,
+        search: None,
+        status: String::new(),
+        height: 0,
+    };
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result.map_err(|e| e.to_string())
+}
+
+/// Finds the page of `path`: items are listed in the index, while modules are guessed from their path.
+fn start_url(index: &ItemIndex, path: &str) -> Option<Url> {
+    let path = path.replace('-', "_");
+    if let Some(entry) = index.get(&path) {
+        return Url::parse(entry.url()).ok();
+    }
+    let root = Url::parse(index.root_url()).ok()?;
+    match path.split_once("::") {
+        None => Some(root),
+        Some((_, modules)) => root.join(&(modules.replace("::", "/") + "/index.html")).ok(),
+    }
+}
+
+fn load(client: &DocsClient, url: &str) -> paradocs::Result<(Url, Arc<Document>)> {
+    let (url, document) = client.get_document_url(url)?;
+    let url = Url::parse(&url).map_err(|_| paradocs::Error::InvalidUrl(url))?;
+    Ok((url, document))
+}
+
+impl App {
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Release && !self.handle(key) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn page(&mut self) -> &mut Page {
+        &mut self.history[self.current]
+    }
+
+    /// Returns `false` to quit.
+    fn handle(&mut self, key: KeyEvent) -> bool {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return false;
+        }
+        if self.search.is_some() {
+            self.handle_search(key);
+            return true;
+        }
+        self.status.clear();
+        let height = self.height.max(1);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Down | KeyCode::Char('j') => self.page().scroll_by(1, height),
+            KeyCode::Up | KeyCode::Char('k') => self.page().scroll_by(-1, height),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.page().scroll_by(height as isize, height),
+            KeyCode::PageUp => self.page().scroll_by(-(height as isize), height),
+            KeyCode::Home | KeyCode::Char('g') => self.page().scroll = 0,
+            KeyCode::End | KeyCode::Char('G') => self.page().scroll_by(isize::MAX, height),
+            KeyCode::Tab | KeyCode::Char('n') => self.page().select_link(1, height),
+            KeyCode::BackTab | KeyCode::Char('N') => self.page().select_link(-1, height),
+            KeyCode::Enter => {
+                let page = &self.history[self.current];
+                if let Some(link) = page.selected.map(|selected| page.links[selected].url.clone()) {
+                    self.open(link);
+                }
+            }
+            KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') => self.current = self.current.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => self.current = (self.current + 1).min(self.history.len() - 1),
+            KeyCode::Char('/') => {
+                if self.index.is_some() {
+                    self.search = Some(Search {
+                        query: String::new(),
+                        results: Vec::new(),
+                        state: ListState::default(),
+                    });
+                } else {
+                    self.status = "search is unavailable".to_owned();
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn handle_search(&mut self, key: KeyEvent) {
+        let search = match &mut self.search {
+            Some(search) => search,
+            None => return,
+        };
+        match key.code {
+            KeyCode::Esc => self.search = None,
+            KeyCode::Down | KeyCode::Tab => search.state.select_next(),
+            KeyCode::Up | KeyCode::BackTab => search.state.select_previous(),
+            KeyCode::Enter => {
+                let url = search
+                    .state
+                    .selected()
+                    .and_then(|selected| search.results.get(selected))
+                    .and_then(|entry| Url::parse(entry.url()).ok());
+                if let Some(url) = url {
+                    self.search = None;
+                    self.open(url);
+                }
+            }
+            KeyCode::Backspace => {
+                search.query.pop();
+                self.update_results();
+            }
+            KeyCode::Char(c) => {
+                search.query.push(c);
+                self.update_results();
+            }
+            _ => {}
+        }
+    }
+
+    fn update_results(&mut self) {
+        if let (Some(search), Some(index)) = (&mut self.search, &self.index) {
+            search.results = index.search(&search.query).into_iter().cloned().collect();
+            search.state.select(if search.results.is_empty() { None } else { Some(0) });
+        }
+    }
+
+    /// Loads `url` as a new page, dropping the pages ahead in history.
+    fn open(&mut self, mut url: Url) {
+        url.set_fragment(None);
+        if url == self.history[self.current].url {
+            return;
+        }
+        match load(&self.client, url.as_str()) {
+            Ok((url, document)) => {
+                self.history.truncate(self.current + 1);
+                self.history.push(Page::new(url, document));
+                self.current = self.history.len() - 1;
+            }
+            Err(e) => self.status = format!("cannot load {}: {}", url, e),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [content, status] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let block = Block::bordered().title(Span::styled(
+            self.history[self.current].document.title().to_owned(),
+            Style::new().add_modifier(Modifier::BOLD),
+        ));
+        let inner = block.inner(content);
+        self.height = inner.height as usize;
+
+        let page = self.page();
+        page.layout(inner.width as usize);
+        let visible: Vec<Line> = page
+            .lines
+            .iter()
+            .enumerate()
+            .skip(page.scroll)
+            .take(inner.height as usize)
+            .map(|(number, line)| page.highlight(number, line))
+            .collect();
+        let position = format!("{}/{} ", (page.scroll + 1).min(page.lines.len()), page.lines.len());
+        frame.render_widget(TextView::new(visible).block(block), content);
+
+        let message = if self.status.is_empty() { HELP } else { &self.status };
+        let [message_area, position_area] =
+            Layout::horizontal([Constraint::Min(1), Constraint::Length(position.len() as u16)]).areas(status);
+        frame.render_widget(Span::styled(message.to_owned(), Style::new().add_modifier(Modifier::DIM)), message_area);
+        frame.render_widget(Span::raw(position), position_area);
+
+        if let Some(search) = &mut self.search {
+            let area = popup(frame.area());
+            frame.render_widget(Clear, area);
+            let block = Block::bordered().title(format!("Search {}", self.history[self.current].crate_name()));
+            let inner = block.inner(area);
+            frame.render_widget(block, area);
+            let [input, results] = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(inner);
+            frame.render_widget(Line::from(format!("/{}", search.query)), input);
+            let items: Vec<ListItem> = search
+                .results
+                .iter()
+                .map(|entry| {
+                    ListItem::new(Line::from(vec![
+                        Span::raw(entry.path().to_owned()),
+                        Span::styled(
+                            format!("  {}", kind_heading(entry.kind())),
+                            Style::new().add_modifier(Modifier::DIM),
+                        ),
+                    ]))
+                })
+                .collect();
+            let list = List::new(items).highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+            frame.render_stateful_widget(list, results, &mut search.state);
+        }
+    }
+}
+
+fn popup(area: Rect) -> Rect {
+    let [_, area, _] = Layout::vertical([Constraint::Percentage(15), Constraint::Percentage(70), Constraint::Fill(1)])
+        .areas(area);
+    let [_, area, _] = Layout::horizontal([Constraint::Percentage(15), Constraint::Percentage(70), Constraint::Fill(1)])
+        .areas(area);
+    area
+}
+
+impl Page {
+    fn new(url: Url, document: Arc<Document>) -> Self {
+        Page {
+            url,
+            document,
+            width: 0,
+            lines: Vec::new(),
+            links: Vec::new(),
+            scroll: 0,
+            selected: None,
+        }
+    }
+
+    fn crate_name(&self) -> &str {
+        self.url.path_segments().and_then(|mut segments| segments.nth(2)).unwrap_or_default()
+    }
+
+    /// Lays the document out again when the width changes.
+    fn layout(&mut self, width: usize) {
+        if width == self.width {
+            return;
+        }
+        let mut layout = PageLayout::new(&self.url, width.max(20));
+        layout.document(&self.document);
+        self.width = width;
+        self.lines = layout.lines;
+        self.links = layout.links;
+        self.selected = None;
+        self.scroll = self.scroll.min(self.lines.len().saturating_sub(1));
+    }
+
+    fn scroll_by(&mut self, lines: isize, height: usize) {
+        let last = self.lines.len().saturating_sub(height);
+        self.scroll = if lines < 0 {
+            self.scroll.saturating_sub(lines.unsigned_abs())
+        } else {
+            self.scroll.saturating_add(lines as usize).min(last)
+        };
+    }
+
+    fn select_link(&mut self, step: isize, height: usize) {
+        if self.links.is_empty() {
+            return;
+        }
+        let count = self.links.len() as isize;
+        let selected = match self.selected {
+            Some(selected) => (selected as isize + step).rem_euclid(count) as usize,
+            // start from the links on screen
+            None => {
+                let first = self.links.iter().position(|link| link.line() >= self.scroll).unwrap_or(0);
+                if step < 0 { (first + self.links.len() - 1) % self.links.len() } else { first }
+            }
+        };
+        self.selected = Some(selected);
+        let line = self.links[selected].line();
+        if line < self.scroll || line >= self.scroll + height {
+            self.scroll = line.saturating_sub(height / 2);
+        }
+    }
+
+    fn highlight<'a>(&self, number: usize, line: &'a Line<'static>) -> Line<'a> {
+        let selected = match self.selected {
+            Some(selected) => &self.links[selected],
+            None => return line.clone(),
+        };
+        let mut line = line.clone();
+        for &(_, span) in selected.spans.iter().filter(|(line, _)| *line == number) {
+            line.spans[span] = line.spans[span].clone().patch_style(Modifier::REVERSED);
+        }
+        line
+    }
+}
+
+impl Link {
+    fn line(&self) -> usize {
+        self.spans.first().map_or(0, |&(line, _)| line)
+    }
+}
+
+/// Word-wraps a document into styled lines, keeping track of where links are.
+struct PageLayout<'a> {
+    base: &'a Url,
+    width: usize,
+    indent: usize,
+    column: usize,
+    spans: Vec<Span<'static>>,
+    lines: Vec<Line<'static>>,
+    links: Vec<Link>,
+}
+
+impl<'a> PageLayout<'a> {
+    fn new(base: &'a Url, width: usize) -> Self {
+        PageLayout {
+            base,
+            width,
+            indent: 0,
+            column: 0,
+            spans: Vec::new(),
+            lines: Vec::new(),
+            links: Vec::new(),
+        }
+    }
+
+    fn end_line(&mut self) {
+        if !self.spans.is_empty() {
+            self.lines.push(Line::from(std::mem::take(&mut self.spans)));
+        }
+        self.column = 0;
+    }
+
+    fn blank(&mut self) {
+        self.end_line();
+        if self.lines.last().is_some_and(|line| !line.spans.is_empty()) {
+            self.lines.push(Line::default());
+        }
+    }
+
+    fn link(&mut self, href: &str) -> Option<usize> {
+        let url = self.base.join(href).ok()?;
+        self.links.push(Link { url, spans: Vec::new() });
+        Some(self.links.len() - 1)
+    }
+
+    fn words(&mut self, text: &str, style: Style, link: Option<usize>) {
+        for word in text.split_inclusive(char::is_whitespace) {
+            let word = word.replace('\n', " ");
+            let span = Span::styled(word, style);
+            let visible = span.content.trim_end().chars().count();
+            if self.column > self.indent && self.column + visible > self.width {
+                self.end_line();
+            }
+            if self.spans.is_empty() {
+                if self.indent > 0 {
+                    self.spans.push(Span::raw(" ".repeat(self.indent)));
+                }
+                self.column = self.indent;
+                if span.content.trim().is_empty() {
+                    continue;
+                }
+            }
+            self.column += span.width();
+            if let Some(link) = link {
+                self.links[link].spans.push((self.lines.len(), self.spans.len()));
+            }
+            self.spans.push(span);
+        }
+    }
+
+    fn text(&mut self, text: &Text) {
+        for component in text.components() {
+            let mut style = Style::new();
+            if component.is_bold() {
+                style = style.add_modifier(Modifier::BOLD);
+            }
+            if component.is_italic() {
+                style = style.add_modifier(Modifier::ITALIC);
+            }
+            if component.is_code() {
+                style = style.fg(Color::Cyan);
+            }
+            let link = component.link().and_then(|href| self.link(href));
+            if link.is_some() {
+                style = style.fg(Color::Blue).add_modifier(Modifier::UNDERLINED);
+            }
+            self.words(component.text(), style, link);
+        }
+        self.end_line();
+    }
+
+    fn code(&mut self, code: &str, indent: usize) {
+        self.end_line();
+        for line in code.lines() {
+            self.lines.push(Line::from(vec![
+                Span::raw(" ".repeat(indent)),
+                Span::styled(line.to_owned(), Style::new().fg(Color::Cyan)),
+            ]));
+        }
+    }
+
+    fn heading(&mut self, title: &str) {
+        self.blank();
+        self.lines.push(Line::styled(
+            title.to_owned(),
+            Style::new().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+        ));
+    }
+
+    fn mark(&mut self, mark: &Mark) {
+        for note in [mark.stability(), mark.deprecated(), mark.features()] {
+            if !note.is_empty() {
+                self.words(note, Style::new().fg(Color::Yellow), None);
+                self.end_line();
+            }
+        }
+    }
+
+    fn document(&mut self, document: &Document) {
+        self.mark(document.mark());
+        if let Some(declaration) = document.declaration() {
+            self.code(declaration, 0);
+        }
+        self.description(document.description());
+
+        walk_sections(self, document.kind(), Sections::all());
+        self.end_line();
+    }
+
+    fn description(&mut self, description: &[Section]) {
+        for section in description {
+            self.section(section, 0);
+        }
+    }
+
+    fn section(&mut self, section: &Section, depth: usize) {
+        if let Some(title) = section.title() {
+            self.blank();
+            self.words(
+                &format!("{} {}", "#".repeat(depth + 1), title),
+                Style::new().add_modifier(Modifier::BOLD),
+                None,
+            );
+            self.end_line();
+        }
+        for paragraph in section.content() {
+            self.blank();
+            match paragraph {
+                Paragraph::Text(text) => self.text(text),
+                Paragraph::List(items) => {
+                    for item in items {
+                        self.words("- ", Style::new(), None);
+                        self.indent += 2;
+                        self.text(item);
+                        self.indent -= 2;
+                    }
+                }
+                Paragraph::Code(code) | Paragraph::InvalidCode(code) => self.code(code, self.indent + 4),
+                Paragraph::SubSection(section) => self.section(section, depth + 1),
+            }
+        }
+    }
+}
+
+impl SectionWriter for PageLayout<'_> {
+    fn module(&mut self, module: &Module) {
+        if !module.re_exports().is_empty() {
+            self.heading("Re-exports");
+            for export in module.re_exports() {
+                self.code(export.declaration(), 4);
+            }
+        }
+        for summary in module.sub_item() {
+            self.heading(kind_heading(summary.item_type()));
+            self.indent = 4;
+            for item in summary.content() {
+                let link = item.link().and_then(|href| self.link(href));
+                let style = match link {
+                    Some(_) => Style::new().fg(Color::Blue).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                    None => Style::new().add_modifier(Modifier::BOLD),
+                };
+                self.words(item.name(), style, link);
+                self.words("  ", Style::new(), None);
+                self.indent = 8;
+                self.text(item.summary());
+                self.indent = 4;
+            }
+            self.indent = 0;
+        }
+    }
+
+    fn simple_items(&mut self, title: &str, items: &[SimpleItem]) {
+        self.heading(title);
+        for item in items {
+            self.code(item.declaration(), 4);
+            self.indent = 8;
+            self.mark(item.mark());
+            self.description(item.description());
+            self.end_line();
+            self.indent = 0;
+        }
+    }
+
+    fn implementations(&mut self, section: ImplSection, implementations: &[Implementation]) {
+        self.heading(section.title());
+        for implementation in implementations {
+            self.code(implementation.impl_code(), 4);
+            if section.lists_items() {
+                for item in implementation.associated_types().iter().chain(implementation.methods()) {
+                    self.code(item.declaration(), 8);
+                    self.indent = 12;
+                    self.mark(item.mark());
+                    self.description(item.description());
+                    self.end_line();
+                    self.indent = 0;
+                }
+            }
+        }
+    }
+
+}
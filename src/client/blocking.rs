@@ -0,0 +1,133 @@
+//! Synchronous wrapper around [`DocsClient`](crate::DocsClient), for callers without an async runtime.
+
+use std::future::Future;
+use std::sync::Arc;
+use futures::executor::{block_on, block_on_stream, BlockingStream};
+use futures::stream::{BoxStream, StreamExt};
+use tokio::runtime::Handle;
+use crate::element::Document;
+use super::{CrateIndex, CrawlOptions, DiskCache, IndexEntry, ItemIndex, ItemPath, MemoryCache, Result};
+
+/// Blocking counterpart of [`crate::DocsClient`].
+///
+/// Each call drives the async client to completion on the calling thread,
+/// so no runtime has to be set up by the caller.
+///
+/// # Panics
+///
+/// Calls panic inside a tokio runtime, whose other tasks they would stall.
+/// Use [`crate::DocsClient`] there instead.
+pub struct DocsClient {
+    inner: super::DocsClient,
+}
+
+/// Items of a crawl, fetched as the iterator is consumed.
+///
+/// Dropping the iterator stops the crawl.
+pub struct CrawlIter<'a> {
+    items: BlockingStream<BoxStream<'a, Result<(ItemPath, Document)>>>,
+}
+
+impl DocsClient {
+    pub fn new(client: super::DocsClient) -> Self {
+        Self { inner: client }
+    }
+
+    pub fn builder() -> super::DocsClientBuilder {
+        super::DocsClientBuilder::new()
+    }
+
+    pub fn cache(&self) -> Option<&DiskCache> {
+        self.inner.cache()
+    }
+
+    pub fn memory_cache(&self) -> &MemoryCache {
+        self.inner.memory_cache()
+    }
+
+    pub fn get_document(&self, path: &str) -> Option<Arc<Document>> {
+        wait(self.inner.get_document(path))
+    }
+
+    pub fn get_document_version(&self, path: &str, version: &str) -> Option<Arc<Document>> {
+        wait(self.inner.get_document_version(path, version))
+    }
+
+    pub fn try_get_document_version(&self, path: &str, version: &str) -> Result<Arc<Document>> {
+        wait(self.inner.try_get_document_version(path, version))
+    }
+
+    pub fn get_document_page(&self, path: &str, version: &str) -> Option<(String, Arc<Document>)> {
+        wait(self.inner.get_document_page(path, version))
+    }
+
+    pub fn get_package_document(&self, package_name: &str, version: &str, path: &str) -> Option<Arc<Document>> {
+        wait(self.inner.get_package_document(package_name, version, path))
+    }
+
+    pub fn resolve_package(&self, crate_name: &str) -> Option<String> {
+        wait(self.inner.resolve_package(crate_name))
+    }
+
+    pub fn crawl(&self, crate_name: &str, options: &CrawlOptions) -> Result<CrateIndex> {
+        wait(self.inner.crawl(crate_name, options))
+    }
+
+    pub fn item_index(&self, crate_name: &str) -> Result<ItemIndex> {
+        wait(self.inner.item_index(crate_name))
+    }
+
+    pub fn item_index_version(&self, crate_name: &str, version: &str) -> Result<ItemIndex> {
+        wait(self.inner.item_index_version(crate_name, version))
+    }
+
+    pub fn search(&self, crate_name: &str, query: &str) -> Result<Vec<IndexEntry>> {
+        wait(self.inner.search(crate_name, query))
+    }
+
+    pub fn get_document_url(&self, url: &str) -> Result<(String, Arc<Document>)> {
+        wait(self.inner.get_document_url(url))
+    }
+
+    /// Crawls `crate_name` like [`crate::DocsClient::crawl_stream`], yielding each item as soon as it is fetched.
+    pub fn crawl_iter(&self, crate_name: &str, options: CrawlOptions) -> CrawlIter<'_> {
+        CrawlIter {
+            items: block_on_stream(self.inner.crawl_stream(crate_name, options).boxed()),
+        }
+    }
+}
+
+impl From<super::DocsClient> for DocsClient {
+    fn from(client: super::DocsClient) -> Self {
+        Self::new(client)
+    }
+}
+
+impl Iterator for CrawlIter<'_> {
+    type Item = Result<(ItemPath, Document)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        assert_outside_runtime();
+        self.items.next()
+    }
+}
+
+impl super::DocsClientBuilder {
+    /// Builds a [`blocking::DocsClient`](DocsClient) with this configuration.
+    pub fn build_blocking(self) -> Result<DocsClient> {
+        self.build().map(DocsClient::new)
+    }
+}
+
+/// Blocking a runtime thread deadlocks a single-threaded runtime and starves
+/// a worker of a threaded one, so it is refused outright.
+fn assert_outside_runtime() {
+    if Handle::try_current().is_ok() {
+        panic!("the blocking paradocs client cannot be used inside a tokio runtime; use paradocs::DocsClient instead");
+    }
+}
+
+fn wait<F: Future>(future: F) -> F::Output {
+    assert_outside_runtime();
+    block_on(future)
+}
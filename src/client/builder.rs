@@ -0,0 +1,200 @@
+use std::path::PathBuf;
+use std::time::Duration;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use reqwest::{Proxy, Url};
+use super::{DiskCache, DocsClient, Error, MemoryCache, Result};
+use super::fixture::FixtureMode;
+use super::runtime::BackgroundRuntime;
+use super::limit::{HostLimiter, RateLimit};
+
+const DEFAULT_BASE_URL: &str = "https://docs.rs";
+const DEFAULT_USER_AGENT: &str = concat!("paradocs/", env!("CARGO_PKG_VERSION"));
+const DEFAULT_RETRIES: u32 = 2;
+const DEFAULT_BACKOFF: Duration = Duration::from_millis(500);
+pub(crate) const MAX_BACKOFF: Duration = Duration::from_secs(30);
+const DEFAULT_RATE_LIMIT: RateLimit = RateLimit {
+    requests_per_second: 5.0,
+    burst: 10,
+};
+const DEFAULT_MAX_CONNECTIONS: usize = 8;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RetryPolicy {
+    pub max_retries: u32,
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Delay before retrying for the `attempt`th time, doubling from the initial backoff.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.backoff.checked_mul(factor).map_or(MAX_BACKOFF, |delay| delay.min(MAX_BACKOFF))
+    }
+}
+
+#[derive(Debug)]
+pub struct DocsClientBuilder {
+    base_url: String,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    retry: RetryPolicy,
+    rate_limit: Option<RateLimit>,
+    max_connections: Option<usize>,
+    proxy: Option<Proxy>,
+    fixtures: Option<FixtureMode>,
+    headers: HeaderMap,
+    user_agent: String,
+    cache: Option<DiskCache>,
+    memory: MemoryCache,
+}
+
+impl Default for DocsClientBuilder {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_owned(),
+            timeout: None,
+            connect_timeout: None,
+            retry: RetryPolicy {
+                max_retries: DEFAULT_RETRIES,
+                backoff: DEFAULT_BACKOFF,
+            },
+            rate_limit: Some(DEFAULT_RATE_LIMIT),
+            max_connections: Some(DEFAULT_MAX_CONNECTIONS),
+            proxy: None,
+            fixtures: None,
+            headers: HeaderMap::new(),
+            user_agent: DEFAULT_USER_AGENT.to_owned(),
+            cache: None,
+            memory: MemoryCache::default(),
+        }
+    }
+}
+
+impl DocsClientBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serves documentation from another docs.rs-compatible host, such as a private mirror.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_owned();
+        self
+    }
+
+    /// Limits how long a whole request, including reading the body, may take.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Retries failed connections and 5xx or 429 responses up to `max_retries` times.
+    ///
+    /// The first retry waits for `backoff`, and every further retry waits
+    /// twice as long as the one before.
+    pub fn retries(mut self, max_retries: u32, backoff: Duration) -> Self {
+        self.retry = RetryPolicy { max_retries, backoff };
+        self
+    }
+
+    /// Sends at most `requests_per_second` requests to each host on average, after an initial `burst`.
+    ///
+    /// Defaults to 5 requests per second with bursts of 10, which keeps bulk
+    /// crawls within the docs.rs usage guidelines.
+    pub fn rate_limit(mut self, requests_per_second: f64, burst: u32) -> Self {
+        self.rate_limit = Some(RateLimit {
+            requests_per_second,
+            burst: burst.max(1),
+        });
+        self
+    }
+
+    /// Sends requests as fast as they are made, for servers under your own control.
+    pub fn no_rate_limit(mut self) -> Self {
+        self.rate_limit = None;
+        self
+    }
+
+    /// Limits how many requests may be in flight at once. Defaults to 8.
+    pub fn max_connections(mut self, connections: usize) -> Self {
+        self.max_connections = Some(connections);
+        self
+    }
+
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Saves every response under `directory`, to be served later by [`replay`](Self::replay).
+    pub fn record(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.fixtures = Some(FixtureMode::Record(directory.into()));
+        self
+    }
+
+    /// Answers requests only from responses saved by [`record`](Self::record), never touching the network.
+    ///
+    /// Requests without a saved response fail with [`Error::MissingFixture`].
+    pub fn replay(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.fixtures = Some(FixtureMode::Replay(directory.into()));
+        self
+    }
+
+    /// Sends `value` as the `name` header with every request.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    pub fn bearer_auth(self, token: &str) -> Result<Self> {
+        let value = HeaderValue::from_str(&format!("Bearer {}", token)).map_err(|_| Error::InvalidHeader)?;
+        Ok(self.header(AUTHORIZATION, value))
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    pub fn disk_cache(mut self, cache: DiskCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn memory_cache(mut self, memory: MemoryCache) -> Self {
+        self.memory = memory;
+        self
+    }
+
+    pub fn build(self) -> Result<DocsClient> {
+        if Url::parse(&self.base_url).is_err() {
+            return Err(Error::InvalidUrl(self.base_url));
+        }
+        let mut client = reqwest::Client::builder()
+            .user_agent(self.user_agent)
+            .default_headers(self.headers);
+        if let Some(timeout) = self.timeout {
+            client = client.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            client = client.connect_timeout(timeout);
+        }
+        if let Some(proxy) = self.proxy {
+            client = client.proxy(proxy);
+        }
+        Ok(DocsClient {
+            client: client.build()?,
+            runtime: BackgroundRuntime::default(),
+            base_url: self.base_url,
+            retry: self.retry,
+            limiter: HostLimiter::new(self.rate_limit, self.max_connections),
+            fixtures: self.fixtures,
+            cache: self.cache,
+            memory: self.memory,
+        })
+    }
+}
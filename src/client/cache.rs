@@ -0,0 +1,289 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use reqwest::Url;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EvictionPolicy {
+    /// Removes the pages which have not been read for the longest time.
+    #[default]
+    LeastRecentlyUsed,
+    /// Removes the pages which were downloaded first.
+    OldestFirst,
+}
+
+/// Persistent store of downloaded pages, shared across runs.
+///
+/// Pages of a concrete crate version never change on docs.rs and are served
+/// straight from disk, while pages looked up through `*` or `latest` are
+/// revalidated with `ETag`/`Last-Modified` before being reused.
+#[derive(Debug)]
+pub struct DiskCache {
+    directory: PathBuf,
+    max_size: Option<u64>,
+    eviction: EvictionPolicy,
+    /// Counted on first use, then kept up to date without rescanning the directory.
+    usage: Mutex<Option<Usage>>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Usage {
+    size: u64,
+    count: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CacheEntry {
+    pub url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: String,
+    stored: u64,
+    accessed: u64,
+}
+
+impl CacheEntry {
+    pub fn new(url: String, etag: Option<String>, last_modified: Option<String>, body: String) -> Self {
+        Self {
+            url,
+            etag,
+            last_modified,
+            body,
+            stored: 0,
+            accessed: 0,
+        }
+    }
+}
+
+impl DiskCache {
+    pub fn new(directory: impl Into<PathBuf>) -> io::Result<Self> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+        Ok(Self {
+            directory,
+            max_size: None,
+            eviction: EvictionPolicy::default(),
+            usage: Mutex::new(None),
+        })
+    }
+
+    /// Evicts pages once the cached bodies grow beyond `bytes`.
+    ///
+    /// Eviction frees a tenth of the limit at once, so the directory is only
+    /// scanned again after that much has been added.
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    pub fn eviction_policy(mut self, policy: EvictionPolicy) -> Self {
+        self.eviction = policy;
+        self
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Total size in bytes of every cached page.
+    pub fn size(&self) -> io::Result<u64> {
+        let mut usage = self.usage.lock().unwrap();
+        Ok(self.usage(&mut usage)?.size)
+    }
+
+    /// Number of cached pages.
+    pub fn len(&self) -> io::Result<usize> {
+        let mut usage = self.usage.lock().unwrap();
+        Ok(self.usage(&mut usage)?.count)
+    }
+
+    pub fn is_empty(&self) -> io::Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    pub fn clear(&self) -> io::Result<()> {
+        let mut usage = self.usage.lock().unwrap();
+        for (key, _, _) in self.entries()? {
+            self.remove(&key)?;
+        }
+        *usage = Some(Usage::default());
+        Ok(())
+    }
+
+    pub(crate) fn get(&self, url: &str) -> Option<CacheEntry> {
+        let _guard = self.usage.lock().unwrap();
+        let key = cache_key(url);
+        let meta = fs::read_to_string(self.meta_path(&key)).ok()?;
+        let mut entry = parse_meta(&meta);
+        entry.body = fs::read_to_string(self.body_path(&key)).ok()?;
+        entry.accessed = now();
+        fs::write(self.meta_path(&key), format_meta(&entry)).ok()?;
+        Some(entry)
+    }
+
+    pub(crate) fn insert(&self, url: &str, entry: &CacheEntry) -> io::Result<()> {
+        let mut usage = self.usage.lock().unwrap();
+        let usage = self.usage(&mut usage)?;
+        let key = cache_key(url);
+        let replaced = fs::metadata(self.body_path(&key)).ok().map(|metadata| metadata.len());
+        let entry = CacheEntry {
+            stored: now(),
+            accessed: now(),
+            ..entry.clone()
+        };
+        fs::write(self.body_path(&key), &entry.body)?;
+        fs::write(self.meta_path(&key), format_meta(&entry))?;
+        usage.size = usage.size.saturating_sub(replaced.unwrap_or(0)) + entry.body.len() as u64;
+        if replaced.is_none() {
+            usage.count += 1;
+        }
+        match self.max_size {
+            Some(max_size) if usage.size > max_size => self.evict(max_size - max_size / 10, usage),
+            _ => Ok(()),
+        }
+    }
+
+    /// The usage counted so far, or counted from the directory on first use.
+    fn usage<'a>(&self, usage: &'a mut Option<Usage>) -> io::Result<&'a mut Usage> {
+        let counted = match *usage {
+            Some(counted) => counted,
+            None => Usage::of(&self.entries()?),
+        };
+        Ok(usage.insert(counted))
+    }
+
+    /// Removes pages until the cached bodies take up at most `target` bytes.
+    fn evict(&self, target: u64, usage: &mut Usage) -> io::Result<()> {
+        let mut entries = self.entries()?;
+        entries.sort_by_key(|(_, _, entry)| match self.eviction {
+            EvictionPolicy::LeastRecentlyUsed => entry.accessed,
+            EvictionPolicy::OldestFirst => entry.stored,
+        });
+        // pages written by other processes are only noticed here
+        *usage = Usage::of(&entries);
+        for (key, size, _) in entries {
+            if usage.size <= target {
+                break;
+            }
+            self.remove(&key)?;
+            usage.size -= size;
+            usage.count -= 1;
+        }
+        Ok(())
+    }
+
+    fn entries(&self) -> io::Result<Vec<(String, u64, CacheEntry)>> {
+        let mut entries = Vec::new();
+        for file in fs::read_dir(&self.directory)? {
+            let path = file?.path();
+            if path.extension().is_none_or(|extension| extension != "meta") {
+                continue;
+            }
+            let key = match path.file_stem().and_then(|stem| stem.to_str()) {
+                Some(key) => key.to_owned(),
+                None => continue,
+            };
+            let entry = fs::read_to_string(&path).map(|meta| parse_meta(&meta))?;
+            let size = fs::metadata(self.body_path(&key)).map_or(0, |metadata| metadata.len());
+            entries.push((key, size, entry));
+        }
+        Ok(entries)
+    }
+
+    fn remove(&self, key: &str) -> io::Result<()> {
+        for path in [self.meta_path(key), self.body_path(key)].iter() {
+            match fs::remove_file(path) {
+                Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn meta_path(&self, key: &str) -> PathBuf {
+        self.directory.join(format!("{}.meta", key))
+    }
+
+    fn body_path(&self, key: &str) -> PathBuf {
+        self.directory.join(format!("{}.html", key))
+    }
+}
+
+impl Usage {
+    fn of(entries: &[(String, u64, CacheEntry)]) -> Self {
+        Usage {
+            size: entries.iter().map(|(_, size, _)| size).sum(),
+            count: entries.len(),
+        }
+    }
+}
+
+/// Whether `url` points into the docs of one exact crate version, which never change.
+///
+/// The version is read from the path below `base_url`, as in `{base_url}/tokio/1.0.0/tokio/`.
+pub(crate) fn is_immutable(base_url: &str, url: &str) -> bool {
+    let (base, url) = match (Url::parse(base_url), Url::parse(url)) {
+        (Ok(base), Ok(url)) => (base, url),
+        _ => return false,
+    };
+    base.origin() == url.origin()
+        && url
+            .path()
+            .strip_prefix(base.path().trim_end_matches('/'))
+            .filter(|path| path.starts_with('/'))
+            .and_then(|path| path.split('/').nth(2))
+            .is_some_and(is_exact_version)
+}
+
+/// Whether `version` names one release, such as `1.0.2`, rather than a requirement or `*`.
+pub(crate) fn is_exact_version(version: &str) -> bool {
+    let core = version.split(['-', '+']).next().unwrap_or_default();
+    core.split('.').count() == 3
+        && core.split('.').all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+}
+
+fn cache_key(url: &str) -> String {
+    // FNV-1a keeps file names stable across builds, unlike std's hasher
+    let hash = url.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+fn format_meta(entry: &CacheEntry) -> String {
+    let mut meta = format!("url {}\nstored {}\naccessed {}\n", entry.url, entry.stored, entry.accessed);
+    if let Some(etag) = &entry.etag {
+        meta += &format!("etag {}\n", etag);
+    }
+    if let Some(last_modified) = &entry.last_modified {
+        meta += &format!("last-modified {}\n", last_modified);
+    }
+    meta
+}
+
+fn parse_meta(meta: &str) -> CacheEntry {
+    let mut entry = CacheEntry::default();
+    for line in meta.lines() {
+        let (key, value) = match line.split_once(' ') {
+            Some(pair) => pair,
+            None => continue,
+        };
+        match key {
+            "url" => entry.url = value.to_owned(),
+            "stored" => entry.stored = value.parse().unwrap_or_default(),
+            "accessed" => entry.accessed = value.parse().unwrap_or_default(),
+            "etag" => entry.etag = Some(value.to_owned()),
+            "last-modified" => entry.last_modified = Some(value.to_owned()),
+            _ => {}
+        }
+    }
+    entry
+}
@@ -0,0 +1,546 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::{Context, Poll, Waker};
+use futures::future::{self, BoxFuture, Either, FutureExt};
+use futures::stream::{self, FuturesUnordered, Stream, StreamExt};
+use reqwest::Url;
+use super::{DocsClient, Error, Result, LATEST_VERSION, normalize_crate_name};
+use crate::element::{Document, DocumentKind, ItemContainer, ItemKind};
+
+pub type ItemPath = String;
+
+const DEFAULT_CONCURRENCY: usize = 4;
+
+#[derive(Clone)]
+pub struct CrawlOptions {
+    max_depth: Option<usize>,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    concurrency: usize,
+    progress: Option<Arc<dyn Fn(CrawlProgress) + Send + Sync>>,
+    cancel: CancelToken,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CrawlProgress {
+    pub done: usize,
+    pub discovered: usize,
+    pub failed: usize,
+}
+
+/// Stops a running crawl once its in-flight requests can be dropped.
+///
+/// Clones share the same state, so one can be handed to the crawl while
+/// another is kept by whoever may need to abort it.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    inner: Arc<CancelState>,
+}
+
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+#[derive(Debug)]
+pub struct IndexedItem {
+    kind: ItemKind,
+    url: String,
+    document: Document,
+    children: Vec<ItemPath>,
+}
+
+#[derive(Debug)]
+pub struct CrateIndex {
+    root: ItemPath,
+    items: BTreeMap<ItemPath, IndexedItem>,
+    aliases: BTreeMap<ItemPath, ItemPath>,
+}
+
+impl Default for CrawlOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: None,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            concurrency: DEFAULT_CONCURRENCY,
+            progress: None,
+            cancel: CancelToken::default(),
+        }
+    }
+}
+
+impl fmt::Debug for CrawlOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CrawlOptions")
+            .field("max_depth", &self.max_depth)
+            .field("include", &self.include)
+            .field("exclude", &self.exclude)
+            .field("concurrency", &self.concurrency)
+            .field("progress", &self.progress.as_ref().map(|_| ".."))
+            .field("cancel", &self.cancel)
+            .finish()
+    }
+}
+
+impl CrawlOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits how many path segments below the crate root are visited.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Only crawls items matching `pattern`, such as `tokio::net` or `tokio::*::TcpStream`.
+    ///
+    /// A pattern also matches every item below the path it describes, and
+    /// `*` matches any part of a single path segment.
+    pub fn include(mut self, pattern: impl Into<String>) -> Self {
+        self.include.push(pattern.into());
+        self
+    }
+
+    /// Skips items matching `pattern`, along with everything below them.
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.exclude.push(pattern.into());
+        self
+    }
+
+    /// Limits how many pages are fetched at the same time. Defaults to 4.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Calls `callback` every time a page is finished or fails.
+    pub fn on_progress(mut self, callback: impl Fn(CrawlProgress) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Arc::new(callback));
+        self
+    }
+
+    pub fn cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    pub(crate) fn should_fetch(&self, path: &str, kind: ItemKind, depth: usize) -> bool {
+        if self.max_depth.is_some_and(|max_depth| depth > max_depth) {
+            return false;
+        }
+        if self.exclude.iter().any(|pattern| path_matches(pattern, path)) {
+            return false;
+        }
+        self.include.is_empty()
+            || self.include.iter().any(|pattern| {
+                path_matches(pattern, path) || (kind == ItemKind::Module && path_leads_to(pattern, path))
+            })
+    }
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        for waker in self.inner.wakers.lock().unwrap().drain(..) {
+            waker.wake();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Completes once [`cancel`](Self::cancel) has been called.
+    pub fn cancelled(&self) -> Cancelled {
+        Cancelled { token: self.clone() }
+    }
+}
+
+pub struct Cancelled {
+    token: CancelToken,
+}
+
+impl Future for Cancelled {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        if self.token.is_cancelled() {
+            return Poll::Ready(());
+        }
+        let mut wakers = self.token.inner.wakers.lock().unwrap();
+        if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+        }
+        drop(wakers);
+        if self.token.is_cancelled() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl IndexedItem {
+    pub fn kind(&self) -> ItemKind {
+        self.kind
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn document(&self) -> &Document {
+        &self.document
+    }
+
+    pub fn children(&self) -> &[ItemPath] {
+        &self.children
+    }
+}
+
+impl CrateIndex {
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    /// Looks up an item, following re-exports to the path it was first found at.
+    pub fn get(&self, path: &str) -> Option<&IndexedItem> {
+        self.items.get(self.canonical_path(path))
+    }
+
+    pub fn document(&self, path: &str) -> Option<&Document> {
+        self.get(path).map(IndexedItem::document)
+    }
+
+    pub fn canonical_path<'a>(&'a self, path: &'a str) -> &'a str {
+        self.aliases.get(path).map_or(path, String::as_str)
+    }
+
+    pub fn aliases(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.aliases.iter().map(|(alias, path)| (alias.as_str(), path.as_str()))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &IndexedItem)> {
+        self.items.iter().map(|(path, item)| (path.as_str(), item))
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+struct PendingItem {
+    path: ItemPath,
+    parent: Option<ItemPath>,
+    kind: ItemKind,
+    url: Url,
+    depth: usize,
+}
+
+enum CrawlEvent {
+    Fetched(PendingItem, Url, Box<Document>),
+    Alias(PendingItem, ItemPath),
+}
+
+type FetchResult = (PendingItem, Result<(Url, Document)>);
+
+struct CrawlState<'a> {
+    client: &'a DocsClient,
+    options: CrawlOptions,
+    crate_base: Option<Url>,
+    queue: VecDeque<PendingItem>,
+    visited: HashMap<String, ItemPath>,
+    in_flight: FuturesUnordered<BoxFuture<'a, FetchResult>>,
+    progress: CrawlProgress,
+    cancelled: bool,
+}
+
+impl DocsClient {
+    /// Fetches every item reachable from the root module of `crate_name`.
+    ///
+    /// Pages which fail to load are left out of the index; only a failure to
+    /// load the crate root is reported as an error, as is a cancelled crawl.
+    pub async fn crawl(&self, crate_name: &str, options: &CrawlOptions) -> Result<CrateIndex> {
+        let mut events = Box::pin(self.crawl_events(crate_name, options.clone()));
+        let mut index = CrateIndex {
+            root: normalize_crate_name(crate_name),
+            items: BTreeMap::new(),
+            aliases: BTreeMap::new(),
+        };
+        while let Some(event) = events.next().await {
+            match event {
+                Ok(CrawlEvent::Fetched(item, url, document)) => index.insert(item, url, *document),
+                Ok(CrawlEvent::Alias(item, path)) => index.alias(item, path),
+                Err(Error::Cancelled) => return Err(Error::Cancelled),
+                Err(error) if index.is_empty() => return Err(error),
+                Err(_) => {}
+            }
+        }
+        Ok(index)
+    }
+
+    /// Crawls `crate_name` like [`crawl`](Self::crawl), yielding each item as soon as it is fetched.
+    ///
+    /// Items reachable through several re-exports are only yielded once, at the
+    /// path they were first discovered at. A cancelled crawl ends with [`Error::Cancelled`].
+    pub fn crawl_stream(
+        &self,
+        crate_name: &str,
+        options: CrawlOptions,
+    ) -> impl Stream<Item = Result<(ItemPath, Document)>> + Send + '_ {
+        self.crawl_events(crate_name, options).filter_map(|event| {
+            future::ready(match event {
+                Ok(CrawlEvent::Fetched(item, _, document)) => Some(Ok((item.path, *document))),
+                Ok(CrawlEvent::Alias(..)) => None,
+                Err(error) => Some(Err(error)),
+            })
+        })
+    }
+
+    fn crawl_events(
+        &self,
+        crate_name: &str,
+        options: CrawlOptions,
+    ) -> impl Stream<Item = Result<CrawlEvent>> + Send + '_ {
+        let crate_name = normalize_crate_name(crate_name);
+        stream::once(async move {
+            let package_name = self
+                .resolve_package(&crate_name)
+                .await
+                .ok_or_else(|| Error::PackageNotFound(crate_name.clone()))?;
+            let root_url = self.crate_url(&package_name, LATEST_VERSION, &crate_name) + "/index.html";
+            let root_url = Url::parse(&root_url).map_err(|_| Error::InvalidUrl(root_url))?;
+            let root = PendingItem {
+                path: crate_name,
+                parent: None,
+                kind: ItemKind::Module,
+                url: root_url,
+                depth: 0,
+            };
+            Ok(CrawlState::new(self, options, root))
+        })
+        .flat_map(|state: Result<CrawlState>| match state {
+            Ok(state) => state.into_stream().left_stream(),
+            Err(error) => stream::once(future::ready(Err(error))).right_stream(),
+        })
+    }
+}
+
+impl<'a> CrawlState<'a> {
+    fn new(client: &'a DocsClient, options: CrawlOptions, root: PendingItem) -> Self {
+        let mut queue = VecDeque::new();
+        queue.push_back(root);
+        Self {
+            client,
+            options,
+            crate_base: None,
+            queue,
+            visited: HashMap::new(),
+            in_flight: FuturesUnordered::new(),
+            progress: CrawlProgress {
+                discovered: 1,
+                ..CrawlProgress::default()
+            },
+            cancelled: false,
+        }
+    }
+
+    fn into_stream(self) -> impl Stream<Item = Result<CrawlEvent>> + Send + 'a {
+        stream::unfold(self, |mut state| async move {
+            let event = state.next_event().await?;
+            Some((event, state))
+        })
+    }
+
+    async fn next_event(&mut self) -> Option<Result<CrawlEvent>> {
+        if self.cancelled {
+            return None;
+        }
+        if self.options.cancel.is_cancelled() {
+            self.cancelled = true;
+            return Some(Err(Error::Cancelled));
+        }
+        while self.in_flight.len() < self.options.concurrency {
+            let item = match self.queue.pop_front() {
+                Some(item) => item,
+                None => break,
+            };
+            if let Some(path) = self.visited.get(&page_key(&item.url)) {
+                let path = path.clone();
+                self.progress.done += 1;
+                self.report();
+                return Some(Ok(CrawlEvent::Alias(item, path)));
+            }
+            self.visited.insert(page_key(&item.url), item.path.clone());
+            let client = self.client;
+            self.in_flight.push(
+                async move {
+                    let result = client.fetch_document(item.url.as_str()).await;
+                    (item, result)
+                }
+                .boxed(),
+            );
+        }
+
+        let cancelled = self.options.cancel.cancelled();
+        let (item, result) = match future::select(self.in_flight.next(), cancelled).await {
+            Either::Left((Some(fetched), _)) => fetched,
+            Either::Left((None, _)) => return None,
+            Either::Right(_) => {
+                self.cancelled = true;
+                return Some(Err(Error::Cancelled));
+            }
+        };
+        let (url, document) = match result {
+            Ok(fetched) => fetched,
+            Err(error) => {
+                self.progress.failed += 1;
+                self.report();
+                return Some(Err(error));
+            }
+        };
+
+        self.progress.done += 1;
+        if let Some(path) = self.visited.get(&page_key(&url)) {
+            if *path != item.path {
+                let path = path.clone();
+                self.report();
+                return Some(Ok(CrawlEvent::Alias(item, path)));
+            }
+        }
+        self.visited.insert(page_key(&url), item.path.clone());
+        if item.parent.is_none() {
+            self.crate_base = url.join("./").ok();
+        }
+        if let Some(crate_base) = &self.crate_base {
+            let children = discover_children(&item, &url, &document, crate_base, &self.options);
+            self.progress.discovered += children.len();
+            self.queue.extend(children);
+        }
+        self.report();
+        Some(Ok(CrawlEvent::Fetched(item, url, Box::new(document))))
+    }
+
+    fn report(&self) {
+        if let Some(callback) = &self.options.progress {
+            callback(self.progress);
+        }
+    }
+}
+
+impl CrateIndex {
+    fn insert(&mut self, item: PendingItem, url: Url, document: Document) {
+        if let Some(parent) = item.parent.as_ref().and_then(|parent| self.items.get_mut(parent)) {
+            parent.children.push(item.path.clone());
+        }
+        self.items.insert(item.path, IndexedItem {
+            kind: item.kind,
+            url: url.into(),
+            document,
+            children: Vec::new(),
+        });
+    }
+
+    fn alias(&mut self, item: PendingItem, path: ItemPath) {
+        if let Some(parent) = item.parent.as_ref().and_then(|parent| self.items.get_mut(parent)) {
+            parent.children.push(item.path.clone());
+        }
+        self.aliases.insert(item.path, path);
+    }
+}
+
+fn discover_children(
+    item: &PendingItem,
+    url: &Url,
+    document: &Document,
+    crate_base: &Url,
+    options: &CrawlOptions,
+) -> Vec<PendingItem> {
+    let module = match document.kind() {
+        DocumentKind::Crate(module) | DocumentKind::Module(module) => module,
+        _ => return Vec::new(),
+    };
+    let depth = item.depth + 1;
+    module
+        .sub_item()
+        .iter()
+        .flat_map(|section| section.content().iter().map(move |summary| (section.item_type(), summary)))
+        .filter_map(|(kind, summary)| {
+            let path = format!("{}::{}", item.path, summary.name());
+            if !options.should_fetch(&path, kind, depth) {
+                return None;
+            }
+            let child_url = url.join(summary.link()?).ok()?;
+            if !child_url.as_str().starts_with(crate_base.as_str()) {
+                return None;
+            }
+            Some(PendingItem {
+                path,
+                parent: Some(item.path.clone()),
+                kind,
+                url: child_url,
+                depth,
+            })
+        })
+        .collect()
+}
+
+fn page_key(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url.set_query(None);
+    url.into()
+}
+
+/// Whether `segment` matches `pattern`, in which `*` matches any part of it.
+pub(crate) fn segment_matches(pattern: &str, segment: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let mut rest = match segment.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let last = match parts.pop() {
+        Some(last) => last,
+        None => return rest.is_empty(),
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// Whether `path` is the item described by `pattern` or lies below it.
+pub(crate) fn path_matches(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split("::").collect();
+    let path: Vec<&str> = path.split("::").collect();
+    path.len() >= pattern.len()
+        && pattern.iter().zip(&path).all(|(pattern, segment)| segment_matches(pattern, segment))
+}
+
+/// Whether items matching `pattern` may lie below `path`.
+pub(crate) fn path_leads_to(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split("::").collect();
+    let path: Vec<&str> = path.split("::").collect();
+    path.len() < pattern.len()
+        && pattern.iter().zip(&path).all(|(pattern, segment)| segment_matches(pattern, segment))
+}
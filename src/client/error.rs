@@ -0,0 +1,62 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum Error {
+    Request(reqwest::Error),
+    Status { url: String, status: u16 },
+    Parse { url: String },
+    PackageNotFound(String),
+    /// The package has no item at this path.
+    ItemNotFound(String),
+    InvalidHeader,
+    InvalidUrl(String),
+    Io(io::Error),
+    MissingFixture { method: String, url: String },
+    InvalidFixture(PathBuf),
+    /// The crawl was stopped through its [`CancelToken`](super::CancelToken).
+    Cancelled,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Request(error) => write!(f, "request failed: {}", error),
+            Error::Status { url, status } => write!(f, "{} responded with status {}", url, status),
+            Error::Parse { url } => write!(f, "{} is not a documentation page", url),
+            Error::PackageNotFound(crate_name) => write!(f, "no package on docs.rs provides {}", crate_name),
+            Error::ItemNotFound(path) => write!(f, "no documentation found for {}", path),
+            Error::InvalidHeader => write!(f, "header value contains invalid characters"),
+            Error::InvalidUrl(url) => write!(f, "{} is not a valid url", url),
+            Error::Io(error) => write!(f, "i/o error: {}", error),
+            Error::MissingFixture { method, url } => write!(f, "no recorded response for {} {}", method, url),
+            Error::InvalidFixture(path) => write!(f, "recorded response at {} is malformed", path.display()),
+            Error::Cancelled => write!(f, "crawl was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Request(error) => Some(error),
+            Error::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(error: reqwest::Error) -> Self {
+        Error::Request(error)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
@@ -0,0 +1,106 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use reqwest::{Method, StatusCode, Url};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use super::{Error, HttpResponse, Result};
+
+/// Where requests are answered from when not talking to the network directly.
+#[derive(Debug, Clone)]
+pub(crate) enum FixtureMode {
+    /// Sends requests as usual and saves every response under the directory.
+    Record(PathBuf),
+    /// Answers requests only from responses saved under the directory.
+    Replay(PathBuf),
+}
+
+/// Path of the fixture for a request, such as `docs.rs/tokio/%2A/tokio/index.html.get`.
+///
+/// Keeping the layout of the url makes fixtures easy to find and review.
+pub(crate) fn fixture_path(directory: &Path, method: &Method, url: &str) -> PathBuf {
+    let mut path = directory.to_path_buf();
+    let url = match Url::parse(url) {
+        Ok(url) => url,
+        Err(_) => return path.join(format!("{}.{}", escape_segment(url), method_extension(method))),
+    };
+    path.push(escape_segment(url.host_str().unwrap_or("localhost")));
+    let segments: Vec<&str> = url.path_segments().map_or_else(Vec::new, |segments| segments.collect());
+    let (last, parents) = segments.split_last().unwrap_or((&"", &[]));
+    for segment in parents {
+        path.push(escape_segment(segment));
+    }
+    let mut file_name = escape_segment(last);
+    if let Some(query) = url.query() {
+        file_name.push('@');
+        file_name.push_str(&escape_segment(query));
+    }
+    path.push(format!("{}.{}", file_name, method_extension(method)));
+    path
+}
+
+pub(crate) fn save(directory: &Path, method: &Method, url: &str, response: &HttpResponse) -> Result<()> {
+    let path = fixture_path(directory, method, url);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut content = format!("{} {}\n", response.status.as_u16(), response.url);
+    for (name, value) in &response.headers {
+        if let Ok(value) = value.to_str() {
+            content += &format!("{}: {}\n", name, value);
+        }
+    }
+    content.push('\n');
+    content += &response.body;
+    fs::write(path, content)?;
+    Ok(())
+}
+
+pub(crate) fn load(directory: &Path, method: &Method, url: &str) -> Result<HttpResponse> {
+    let path = fixture_path(directory, method, url);
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            return Err(Error::MissingFixture {
+                method: method.to_string(),
+                url: url.to_owned(),
+            })
+        }
+        Err(error) => return Err(error.into()),
+    };
+    let invalid = || Error::InvalidFixture(path.clone());
+    let (head, body) = content.split_once("\n\n").unwrap_or((&content, ""));
+    let mut lines = head.lines();
+    let (status, final_url) = lines.next().and_then(|line| line.split_once(' ')).ok_or_else(invalid)?;
+    let status = status.parse().ok().and_then(|status| StatusCode::from_u16(status).ok());
+    let mut headers = HeaderMap::new();
+    for line in lines {
+        let (name, value) = line.split_once(':').ok_or_else(invalid)?;
+        let name = HeaderName::from_bytes(name.trim().as_bytes()).map_err(|_| invalid())?;
+        let value = HeaderValue::from_str(value.trim()).map_err(|_| invalid())?;
+        headers.append(name, value);
+    }
+    Ok(HttpResponse {
+        url: Url::parse(final_url).map_err(|_| invalid())?,
+        status: status.ok_or_else(invalid)?,
+        headers,
+        body: body.to_owned(),
+    })
+}
+
+fn method_extension(method: &Method) -> String {
+    method.as_str().to_ascii_lowercase()
+}
+
+fn escape_segment(segment: &str) -> String {
+    if segment.is_empty() {
+        return "%".to_owned();
+    }
+    let mut escaped = String::new();
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'.' | b'_' | b'-' => escaped.push(byte as char),
+            _ => escaped += &format!("%{:02X}", byte),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use async_lock::{Semaphore, SemaphoreGuard};
+use futures_timer::Delay;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RateLimit {
+    pub requests_per_second: f64,
+    pub burst: u32,
+}
+
+/// Keeps requests to each host under a token bucket rate and a connection limit.
+#[derive(Debug)]
+pub(crate) struct HostLimiter {
+    rate: Option<RateLimit>,
+    buckets: Mutex<HashMap<String, Bucket>>,
+    connections: Option<Semaphore>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    blocked_until: Option<Instant>,
+}
+
+impl HostLimiter {
+    pub fn new(rate: Option<RateLimit>, max_connections: Option<usize>) -> Self {
+        Self {
+            rate,
+            buckets: Mutex::new(HashMap::new()),
+            connections: max_connections.map(|connections| Semaphore::new(connections.max(1))),
+        }
+    }
+
+    /// Waits until a request to `host` may be sent.
+    ///
+    /// The returned guard holds one of the connections until it is dropped.
+    pub async fn acquire(&self, host: &str) -> Option<SemaphoreGuard<'_>> {
+        let permit = match &self.connections {
+            Some(connections) => Some(connections.acquire().await),
+            None => None,
+        };
+        let wait = self.reserve(host);
+        if wait > Duration::from_secs(0) {
+            Delay::new(wait).await;
+        }
+        permit
+    }
+
+    /// Holds back every request to `host` for `duration`, as asked by a `Retry-After` header.
+    pub fn block(&self, host: &str, duration: Duration) {
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(host.to_owned()).or_insert_with(|| Bucket::new(self.rate));
+        let until = match Instant::now().checked_add(duration) {
+            Some(until) => until,
+            None => return,
+        };
+        if bucket.blocked_until.is_none_or(|blocked_until| blocked_until < until) {
+            bucket.blocked_until = Some(until);
+        }
+    }
+
+    fn reserve(&self, host: &str) -> Duration {
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(host.to_owned()).or_insert_with(|| Bucket::new(self.rate));
+        let now = Instant::now();
+        let mut wait = match bucket.blocked_until {
+            Some(until) if until > now => until - now,
+            _ => {
+                bucket.blocked_until = None;
+                Duration::from_secs(0)
+            }
+        };
+        if let Some(rate) = self.rate {
+            let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * rate.requests_per_second).min(f64::from(rate.burst));
+            bucket.updated = now;
+            // going into debt reserves a slot for this request, so waiters keep their order
+            bucket.tokens -= 1.0;
+            if bucket.tokens < 0.0 {
+                wait = wait.max(Duration::from_secs_f64(-bucket.tokens / rate.requests_per_second));
+            }
+        }
+        wait
+    }
+}
+
+impl Bucket {
+    fn new(rate: Option<RateLimit>) -> Self {
+        Self {
+            tokens: rate.map_or(0.0, |rate| f64::from(rate.burst)),
+            updated: Instant::now(),
+            blocked_until: None,
+        }
+    }
+}
+
+/// Reads a `Retry-After` header given either in seconds or as an HTTP date, capped at `max`.
+///
+/// Dates which have already passed are ignored.
+pub(crate) fn retry_after(headers: &HeaderMap, max: Duration) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    let wait = match value.parse() {
+        Ok(seconds) => Duration::from_secs(seconds),
+        Err(_) => httpdate::parse_http_date(value).ok()?.duration_since(SystemTime::now()).ok()?,
+    };
+    Some(wait.min(max))
+}
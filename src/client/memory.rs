@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use futures::channel::oneshot;
+use crate::element::Document;
+use super::cache::is_exact_version;
+use super::Result;
+
+const DEFAULT_CAPACITY: usize = 64;
+const DEFAULT_LATEST_TTL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct DocumentKey {
+    pub package: String,
+    pub version: String,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum CacheKey {
+    Document(DocumentKey),
+    /// The package providing a crate, by crate name.
+    Package(String),
+}
+
+#[derive(Clone)]
+enum CacheValue {
+    Document(String, Arc<Document>),
+    Package(String),
+}
+
+type Waiter = oneshot::Sender<Option<CacheValue>>;
+
+enum Slot {
+    Ready {
+        value: CacheValue,
+        last_used: u64,
+        expires: Option<Instant>,
+    },
+    Pending(Vec<Waiter>),
+}
+
+/// Least recently used set of parsed documents and resolved package names, kept in memory.
+///
+/// Lookups for a document or package which is already being fetched wait
+/// for that fetch instead of starting another one.
+#[derive(Debug)]
+pub struct MemoryCache {
+    capacity: usize,
+    latest_ttl: Duration,
+    state: Mutex<MemoryState>,
+}
+
+#[derive(Default)]
+struct MemoryState {
+    slots: HashMap<CacheKey, Slot>,
+    ready: usize,
+    clock: u64,
+}
+
+impl std::fmt::Debug for MemoryState {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("MemoryState")
+            .field("ready", &self.ready)
+            .field("pending", &(self.slots.len() - self.ready))
+            .finish()
+    }
+}
+
+impl Default for MemoryCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl MemoryCache {
+    /// Keeps up to `capacity` documents and package names. A capacity of zero
+    /// only merges concurrent lookups.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            latest_ttl: DEFAULT_LATEST_TTL,
+            state: Mutex::new(MemoryState::default()),
+        }
+    }
+
+    /// Fetches documents of `*` or any other inexact version again once they
+    /// are older than `ttl`, as a newer release may have replaced them.
+    /// Defaults to ten minutes.
+    pub fn latest_ttl(mut self, ttl: Duration) -> Self {
+        self.latest_ttl = ttl;
+        self
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().ready
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.slots.retain(|_, slot| matches!(slot, Slot::Pending(_)));
+        state.ready = 0;
+    }
+
+    /// Fetches a document along with the URL it was read from, once per key.
+    pub(crate) async fn get_or_fetch<F, Fut>(&self, key: DocumentKey, fetch: F) -> Result<(String, Arc<Document>)>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<(String, Arc<Document>)>>,
+    {
+        let value = self
+            .get_or_insert(CacheKey::Document(key), || async {
+                fetch().await.map(|(url, document)| CacheValue::Document(url, document))
+            })
+            .await?;
+        match value {
+            CacheValue::Document(url, document) => Ok((url, document)),
+            CacheValue::Package(_) => unreachable!("document keys only hold documents"),
+        }
+    }
+
+    /// Resolves the package providing `crate_name` once, as package names never change.
+    pub(crate) async fn get_or_resolve<F, Fut>(&self, crate_name: &str, resolve: F) -> Result<String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<String>>,
+    {
+        let value = self
+            .get_or_insert(CacheKey::Package(crate_name.to_owned()), || async {
+                resolve().await.map(CacheValue::Package)
+            })
+            .await?;
+        match value {
+            CacheValue::Package(package) => Ok(package),
+            CacheValue::Document(..) => unreachable!("package keys only hold package names"),
+        }
+    }
+
+    async fn get_or_insert<F, Fut>(&self, key: CacheKey, fetch: F) -> Result<CacheValue>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<CacheValue>>,
+    {
+        let receiver = {
+            let mut state = self.state.lock().unwrap();
+            state.clock += 1;
+            let clock = state.clock;
+            let expired = matches!(
+                state.slots.get(&key),
+                Some(Slot::Ready { expires: Some(expires), .. }) if *expires <= Instant::now()
+            );
+            if expired {
+                state.slots.remove(&key);
+                state.ready -= 1;
+            }
+            match state.slots.get_mut(&key) {
+                Some(Slot::Ready { value, last_used, .. }) => {
+                    *last_used = clock;
+                    return Ok(value.clone());
+                }
+                Some(Slot::Pending(waiters)) => {
+                    let (sender, receiver) = oneshot::channel();
+                    waiters.push(sender);
+                    Some(receiver)
+                }
+                None => {
+                    state.slots.insert(key.clone(), Slot::Pending(Vec::new()));
+                    None
+                }
+            }
+        };
+        if let Some(receiver) = receiver {
+            // the fetching lookup was dropped midway if the sender is gone, and
+            // errors cannot be shared, so a failed one is repeated to report why
+            return match receiver.await {
+                Ok(Some(value)) => Ok(value),
+                Ok(None) | Err(_) => fetch().await,
+            };
+        }
+
+        let guard = PendingGuard { cache: self, key: Some(key) };
+        let value = fetch().await;
+        guard.complete(value.as_ref().ok().cloned());
+        value
+    }
+
+    fn complete(&self, key: CacheKey, value: Option<CacheValue>) {
+        let mut state = self.state.lock().unwrap();
+        let waiters = match state.slots.remove(&key) {
+            Some(Slot::Pending(waiters)) => waiters,
+            _ => Vec::new(),
+        };
+        if let Some(value) = &value {
+            if self.capacity > 0 {
+                state.clock += 1;
+                let last_used = state.clock;
+                let expires = match &key {
+                    CacheKey::Document(key) if !is_exact_version(&key.version) => {
+                        Instant::now().checked_add(self.latest_ttl)
+                    }
+                    _ => None,
+                };
+                state.slots.insert(key, Slot::Ready { value: value.clone(), last_used, expires });
+                state.ready += 1;
+                if state.ready > self.capacity {
+                    state.evict_least_recent();
+                }
+            }
+        }
+        drop(state);
+        for waiter in waiters {
+            let _ = waiter.send(value.clone());
+        }
+    }
+}
+
+impl MemoryState {
+    fn evict_least_recent(&mut self) {
+        let oldest = self
+            .slots
+            .iter()
+            .filter_map(|(key, slot)| match slot {
+                Slot::Ready { last_used, .. } => Some((*last_used, key)),
+                Slot::Pending(_) => None,
+            })
+            .min_by_key(|(last_used, _)| *last_used)
+            .map(|(_, key)| key.clone());
+        if let Some(key) = oldest {
+            self.slots.remove(&key);
+            self.ready -= 1;
+        }
+    }
+}
+
+struct PendingGuard<'a> {
+    cache: &'a MemoryCache,
+    key: Option<CacheKey>,
+}
+
+impl PendingGuard<'_> {
+    fn complete(mut self, value: Option<CacheValue>) {
+        if let Some(key) = self.key.take() {
+            self.cache.complete(key, value);
+        }
+    }
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            let mut state = self.cache.state.lock().unwrap();
+            state.slots.remove(&key);
+        }
+    }
+}
@@ -0,0 +1,437 @@
+use std::sync::Arc;
+use kuchiki::traits::TendrilSink;
+use futures_timer::Delay;
+use reqwest::{Method, StatusCode, Url};
+use reqwest::header::{HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use crate::element::{Document, parse_document};
+use cache::{CacheEntry, is_immutable};
+use memory::DocumentKey;
+use builder::{RetryPolicy, MAX_BACKOFF};
+use limit::{HostLimiter, retry_after};
+use fixture::FixtureMode;
+use runtime::BackgroundRuntime;
+
+mod error;
+mod builder;
+mod limit;
+mod fixture;
+mod runtime;
+mod cache;
+mod memory;
+mod crawl;
+mod search;
+mod query;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+pub use error::*;
+pub use builder::DocsClientBuilder;
+pub use cache::{DiskCache, EvictionPolicy};
+pub use memory::MemoryCache;
+pub use crawl::*;
+pub use search::{IndexEntry, ItemIndex};
+pub use query::*;
+
+const LATEST_VERSION: &str = "*";
+
+pub struct DocsClient {
+    client: reqwest::Client,
+    runtime: BackgroundRuntime,
+    base_url: String,
+    retry: RetryPolicy,
+    limiter: HostLimiter,
+    fixtures: Option<FixtureMode>,
+    cache: Option<DiskCache>,
+    memory: MemoryCache,
+}
+
+struct HttpResponse {
+    url: Url,
+    status: StatusCode,
+    headers: HeaderMap,
+    body: String,
+}
+
+struct Page {
+    url: Url,
+    body: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl Page {
+    fn from_cache(entry: &CacheEntry) -> Option<Self> {
+        Some(Page {
+            url: Url::parse(&entry.url).ok()?,
+            body: entry.body.clone(),
+            etag: entry.etag.clone(),
+            last_modified: entry.last_modified.clone(),
+        })
+    }
+}
+
+impl Default for DocsClient {
+    fn default() -> Self {
+        DocsClientBuilder::new()
+            .build()
+            .expect("default client configuration is valid")
+    }
+}
+
+impl DocsClient {
+    pub fn builder() -> DocsClientBuilder {
+        DocsClientBuilder::new()
+    }
+
+    pub fn cache(&self) -> Option<&DiskCache> {
+        self.cache.as_ref()
+    }
+
+    pub fn memory_cache(&self) -> &MemoryCache {
+        &self.memory
+    }
+
+    pub async fn get_document(&self, path: &str) -> Option<Arc<Document>> {
+        self.get_document_version(path, LATEST_VERSION).await
+    }
+
+    /// Looks up `path` in the docs of a particular version, such as `0.2.21` or `^0.2`.
+    pub async fn get_document_version(&self, path: &str, version: &str) -> Option<Arc<Document>> {
+        self.try_get_document_version(path, version).await.ok()
+    }
+
+    /// Like [`get_document_version`](Self::get_document_version), telling why a document could not be loaded.
+    ///
+    /// Only a path docs.rs does not document fails with [`Error::PackageNotFound`]
+    /// or [`Error::ItemNotFound`].
+    pub async fn try_get_document_version(&self, path: &str, version: &str) -> Result<Arc<Document>> {
+        let (_, document) = self.try_get_document_page(path, version).await?;
+        Ok(document)
+    }
+
+    /// Like [`get_document_version`](Self::get_document_version), also returning
+    /// the URL the page was read from after redirects, which relative links in
+    /// the document are resolved against.
+    pub async fn get_document_page(&self, path: &str, version: &str) -> Option<(String, Arc<Document>)> {
+        self.try_get_document_page(path, version).await.ok()
+    }
+
+    async fn try_get_document_page(&self, path: &str, version: &str) -> Result<(String, Arc<Document>)> {
+        let path = normalize_crate_name(path);
+        let crate_name = path.split("::").next().unwrap_or_default();
+        let package_name = self.try_resolve_package(crate_name).await?;
+        self.get_package_page(&package_name, version, &path).await
+    }
+
+    pub async fn get_package_document(&self, package_name: &str, version: &str, path: &str) -> Option<Arc<Document>> {
+        let (_, document) = self.get_package_page(package_name, version, path).await.ok()?;
+        Some(document)
+    }
+
+    async fn get_package_page(&self, package_name: &str, version: &str, path: &str) -> Result<(String, Arc<Document>)> {
+        let key = DocumentKey {
+            package: package_name.to_owned(),
+            version: version.to_owned(),
+            path: path.to_owned(),
+        };
+        self.memory
+            .get_or_fetch(key, || async {
+                let url = self.get_path_url(package_name, version, path).await?;
+                let (url, document) = self.fetch_document(&url).await?;
+                Ok((url.into(), Arc::new(document)))
+            })
+            .await
+    }
+
+    /// Finds the package on docs.rs which provides `crate_name`.
+    ///
+    /// Package names may use either hyphens or underscores, while crate names
+    /// always use underscores, so each spelling is probed in turn and the
+    /// package name is taken from wherever docs.rs redirects.
+    ///
+    /// Resolved names are remembered by the memory cache.
+    pub async fn resolve_package(&self, crate_name: &str) -> Option<String> {
+        self.try_resolve_package(crate_name).await.ok()
+    }
+
+    /// Like [`resolve_package`](Self::resolve_package), failing with
+    /// [`Error::PackageNotFound`] only when docs.rs has none of the spellings.
+    async fn try_resolve_package(&self, crate_name: &str) -> Result<String> {
+        self.memory
+            .get_or_resolve(crate_name, || self.probe_package(crate_name))
+            .await
+    }
+
+    async fn probe_package(&self, crate_name: &str) -> Result<String> {
+        let mut failure = None;
+        for candidate in package_candidates(crate_name) {
+            let url = self.package_url(&candidate);
+            let response = match self.send(Method::HEAD, &url, HeaderMap::new()).await {
+                Ok(response) => response,
+                Err(error) => {
+                    failure = Some(error);
+                    continue;
+                }
+            };
+            if response.status.is_success() {
+                let package_name = response
+                    .url
+                    .as_str()
+                    .strip_prefix(&self.base_url)
+                    .and_then(|path| path.trim_start_matches('/').split('/').next())
+                    .filter(|segment| !segment.is_empty())
+                    .map(str::to_owned);
+                return Ok(package_name.unwrap_or(candidate));
+            }
+            if response.status != StatusCode::NOT_FOUND {
+                failure = Some(Error::Status {
+                    url,
+                    status: response.status.as_u16(),
+                });
+            }
+        }
+        // a spelling which could not be checked may still exist
+        Err(failure.unwrap_or_else(|| Error::PackageNotFound(crate_name.to_owned())))
+    }
+
+    async fn get_path_url(&self, package_name: &str, version: &str, path: &str) -> Result<String> {
+        let path_parts: Vec<&str> = path.splitn(2, "::").collect();
+        let url = self.crate_url(package_name, version, path_parts[0]);
+        if path_parts.len() < 2 {
+            return Ok(url + "/index.html");
+        }
+        let module = self.find_module(&url, path_parts[1]).await;
+        if let Ok(Some(module)) = module {
+            return Ok(module);
+        }
+        if let Some(item) = self.find_sub_item(&url, path_parts[1]).await? {
+            return Ok(item);
+        }
+        // the item is only known to be missing if the module could be checked too
+        module?;
+        Err(Error::ItemNotFound(path.to_owned()))
+    }
+
+    async fn fetch_document(&self, url: &str) -> Result<(Url, Document)> {
+        let Page { url, body, .. } = self.get_page(url).await?;
+        let dom = kuchiki::parse_html().one(body);
+        let document = parse_document(&dom).ok_or_else(|| Error::Parse {
+            url: url.to_string(),
+        })?;
+        Ok((url, document))
+    }
+
+    async fn get_page(&self, url: &str) -> Result<Page> {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return self.download_page(url, None).await,
+        };
+        let cached = cache.get(url);
+        if let Some(entry) = &cached {
+            // a `*` page redirects to a concrete version, which says nothing about whether it is still the latest
+            if is_immutable(&self.base_url, url) {
+                if let Some(page) = Page::from_cache(entry) {
+                    return Ok(page);
+                }
+            }
+        }
+        let page = self.download_page(url, cached.as_ref()).await?;
+        let entry = CacheEntry::new(
+            page.url.to_string(),
+            page.etag.clone(),
+            page.last_modified.clone(),
+            page.body.clone(),
+        );
+        // a failure to cache a page should not fail the lookup itself
+        let _ = cache.insert(url, &entry);
+        if page.url.as_str() != url && is_immutable(&self.base_url, page.url.as_str()) {
+            let _ = cache.insert(page.url.as_str(), &entry);
+        }
+        Ok(page)
+    }
+
+    async fn download_page(&self, url: &str, cached: Option<&CacheEntry>) -> Result<Page> {
+        let mut headers = HeaderMap::new();
+        if let Some(entry) = cached {
+            let etag = entry.etag.as_deref().and_then(|etag| HeaderValue::from_str(etag).ok());
+            if let Some(etag) = etag {
+                headers.insert(IF_NONE_MATCH, etag);
+            }
+            let last_modified = entry
+                .last_modified
+                .as_deref()
+                .and_then(|last_modified| HeaderValue::from_str(last_modified).ok());
+            if let Some(last_modified) = last_modified {
+                headers.insert(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+        let response = self.send(Method::GET, url, headers).await?;
+        if response.status == StatusCode::NOT_MODIFIED {
+            if let Some(page) = cached.and_then(Page::from_cache) {
+                return Ok(page);
+            }
+        }
+        if !response.status.is_success() {
+            return Err(Error::Status {
+                url: url.to_owned(),
+                status: response.status.as_u16(),
+            });
+        }
+        let header = |name| {
+            response
+                .headers
+                .get(name)
+                .and_then(|value: &HeaderValue| value.to_str().ok())
+                .map(str::to_owned)
+        };
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        Ok(Page {
+            url: response.url,
+            body: response.body,
+            etag,
+            last_modified,
+        })
+    }
+
+    async fn find_sub_item(&self, url: &str, sub_path: &str) -> Result<Option<String>> {
+        let index_url = url.to_owned() + "/all.html";
+        let data = match self.get_page(&index_url).await {
+            Ok(page) => page.body,
+            Err(Error::Status { status: 404, .. }) => return Ok(None),
+            Err(error) => return Err(error),
+        };
+        let index_page = kuchiki::parse_html().one(data.as_ref());
+        let href = index_page
+            .select(".docblock > li > a")
+            .unwrap()
+            .find(|a| a.text_contents() == sub_path)
+            .and_then(|a| a.attributes.borrow().get("href").map(str::to_owned));
+        Ok(href.map(|href| url.to_owned() + "/" + &href))
+    }
+
+    async fn find_module(&self, url: &str, sub_path: &str) -> Result<Option<String>> {
+        let mut check_url = url.to_owned();
+        for module in sub_path.split("::") {
+            check_url.push('/');
+            check_url.push_str(module);
+        }
+        let response = self.send(Method::HEAD, &check_url, HeaderMap::new()).await?;
+        if response.status.is_success() {
+            Ok(Some(check_url))
+        } else if response.status == StatusCode::NOT_FOUND {
+            Ok(None)
+        } else {
+            Err(Error::Status {
+                url: check_url,
+                status: response.status.as_u16(),
+            })
+        }
+    }
+
+    /// Sends a request and reads its response, retrying connection failures and 5xx or 429 responses.
+    async fn send(&self, method: Method, url: &str, headers: HeaderMap) -> Result<HttpResponse> {
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_owned))
+            .unwrap_or_default();
+        let mut attempt = 0;
+        loop {
+            let permit = match &self.fixtures {
+                Some(FixtureMode::Replay(_)) => None,
+                _ => self.limiter.acquire(&host).await,
+            };
+            let result = self.send_once(method.clone(), url, headers.clone()).await;
+            drop(permit);
+
+            let (retryable, wait) = match &result {
+                Ok(response) => {
+                    let throttled = response.status == StatusCode::TOO_MANY_REQUESTS
+                        || response.status == StatusCode::SERVICE_UNAVAILABLE;
+                    let wait = if throttled { retry_after(&response.headers, MAX_BACKOFF) } else { None };
+                    if let Some(wait) = wait {
+                        self.limiter.block(&host, wait);
+                    }
+                    (response.status.is_server_error() || throttled, wait)
+                }
+                Err(Error::Request(error)) => (error.is_timeout() || error.is_connect(), None),
+                Err(_) => (false, None),
+            };
+            if !retryable || attempt >= self.retry.max_retries {
+                return result;
+            }
+            attempt += 1;
+            // a Retry-After delay is already enforced by the limiter for the whole host
+            if wait.is_none() {
+                Delay::new(self.retry.delay(attempt)).await;
+            }
+        }
+    }
+
+    async fn send_once(&self, method: Method, url: &str, headers: HeaderMap) -> Result<HttpResponse> {
+        match &self.fixtures {
+            Some(FixtureMode::Replay(directory)) => fixture::load(directory, &method, url),
+            Some(FixtureMode::Record(directory)) => {
+                let response = self.send_live(method.clone(), url, headers).await?;
+                fixture::save(directory, &method, url, &response)?;
+                Ok(response)
+            }
+            None => self.send_live(method, url, headers).await,
+        }
+    }
+
+    async fn send_live(&self, method: Method, url: &str, headers: HeaderMap) -> Result<HttpResponse> {
+        let request = self.client.request(method.clone(), url).headers(headers);
+        self.runtime
+            .run(async move {
+                let response = request.send().await?;
+                let final_url = response.url().clone();
+                let status = response.status();
+                let headers = response.headers().clone();
+                let body = if method == Method::HEAD {
+                    String::new()
+                } else {
+                    response.text().await?
+                };
+                Ok(HttpResponse {
+                    url: final_url,
+                    status,
+                    headers,
+                    body,
+                })
+            })
+            .await
+    }
+
+    pub(crate) fn crate_url(&self, package_name: &str, version: &str, crate_name: &str) -> String {
+        format!(
+            "{base}/{package}/{version}/{crate}",
+            base = self.base_url,
+            package = package_name,
+            version = version,
+            crate = crate_name
+        )
+    }
+
+    fn package_url(&self, package_name: &str) -> String {
+        format!("{base}/{package}", base = self.base_url, package = package_name)
+    }
+}
+
+fn normalize_crate_name(path: &str) -> String {
+    let mut parts = path.splitn(2, "::");
+    let crate_name = parts.next().unwrap_or_default().replace('-', "_");
+    match parts.next() {
+        Some(rest) => crate_name + "::" + rest,
+        None => crate_name,
+    }
+}
+
+fn package_candidates(crate_name: &str) -> Vec<String> {
+    let mut candidates = vec![crate_name.to_owned()];
+    let hyphenated = crate_name.replace('_', "-");
+    if hyphenated != crate_name {
+        candidates.push(hyphenated);
+    }
+    candidates
+}
@@ -0,0 +1,469 @@
+use std::collections::HashMap;
+use super::CrateIndex;
+use super::crawl::{path_matches, segment_matches};
+use crate::element::{Code, Declared, Document, DocumentKind, Implementable, Implementation, ItemKind, Mark, Marked, SimpleItem};
+
+/// Parts of an item which a query can select instead of the item itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemberKind {
+    Field,
+    Variant,
+    /// Methods of impl blocks, and required methods of traits.
+    Method,
+    AssociatedType,
+}
+
+/// Selects items of a crawled crate, or members of those items.
+///
+/// Filters added with different methods must all match. Several kinds,
+/// members or paths widen the query instead, as any of them may match.
+///
+/// ```no_run
+/// # async fn run(client: paradocs::DocsClient) -> paradocs::Result<()> {
+/// use paradocs::{CrawlOptions, MemberKind, Query};
+/// use paradocs::element::ItemKind;
+///
+/// let index = client.crawl("tokio", &CrawlOptions::new().include("tokio::net")).await?;
+/// let query = Query::new()
+///     .path("tokio::net")
+///     .kind(ItemKind::Struct)
+///     .member(MemberKind::Method)
+///     .declaration("async")
+///     .deprecated(false);
+/// for found in index.query(&query) {
+///     println!("{}", found.path());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    kinds: Vec<ItemKind>,
+    paths: Vec<String>,
+    members: Vec<MemberKind>,
+    declarations: Vec<String>,
+    deprecated: Option<bool>,
+    unstable: Option<bool>,
+    features: Vec<String>,
+    implements: Vec<String>,
+    in_impls: Vec<String>,
+    inherent: bool,
+}
+
+/// A member of an item, such as a method.
+#[derive(Debug, Clone, Copy)]
+pub struct Member<'a> {
+    kind: MemberKind,
+    item: &'a SimpleItem,
+    implementation: Option<&'a Implementation>,
+}
+
+/// An item or member found by a [`Query`], along with the document of the item.
+#[derive(Debug, Clone)]
+pub struct QueryMatch<'a> {
+    path: String,
+    kind: ItemKind,
+    document: &'a Document,
+    member: Option<Member<'a>>,
+}
+
+impl Query {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only matches items of `kind`, or members of such items.
+    pub fn kind(mut self, kind: ItemKind) -> Self {
+        self.kinds.push(kind);
+        self
+    }
+
+    /// Only matches paths matching `pattern`, such as `tokio::net` or `tokio::*::TcpStream`.
+    ///
+    /// As with [`CrawlOptions::include`](super::CrawlOptions::include), a pattern
+    /// also matches everything below the path it describes. Members are matched
+    /// by the path of their item followed by their name.
+    pub fn path(mut self, pattern: impl Into<String>) -> Self {
+        self.paths.push(pattern.into());
+        self
+    }
+
+    /// Matches members of `kind` instead of whole items.
+    pub fn member(mut self, kind: MemberKind) -> Self {
+        self.members.push(kind);
+        self
+    }
+
+    /// Only matches declarations containing `pattern`, in which `*` matches anything.
+    ///
+    /// Whitespace is collapsed before matching, so `async fn` also matches
+    /// declarations broken across lines.
+    pub fn declaration(mut self, pattern: impl Into<String>) -> Self {
+        self.declarations.push(pattern.into());
+        self
+    }
+
+    /// Only matches deprecated items, or only those which are not.
+    ///
+    /// Members of deprecated items count as deprecated too.
+    pub fn deprecated(mut self, deprecated: bool) -> Self {
+        self.deprecated = Some(deprecated);
+        self
+    }
+
+    /// Only matches unstable items, or only those which are not.
+    pub fn unstable(mut self, unstable: bool) -> Self {
+        self.unstable = Some(unstable);
+        self
+    }
+
+    /// Only matches items whose required features mention `pattern`.
+    pub fn feature(mut self, pattern: impl Into<String>) -> Self {
+        self.features.push(pattern.into());
+        self
+    }
+
+    /// Only matches items implementing a trait named `pattern`, or members of such items.
+    ///
+    /// The pattern matches the trait without generic arguments, either as
+    /// written in the impl block or by its last path segment.
+    pub fn implements(mut self, pattern: impl Into<String>) -> Self {
+        self.implements.push(pattern.into());
+        self
+    }
+
+    /// Only matches members of impl blocks of a trait named `pattern`.
+    ///
+    /// Whole items are never in an impl block, so without [`member`](Self::member)
+    /// the query matches nothing.
+    pub fn in_impl(mut self, pattern: impl Into<String>) -> Self {
+        self.in_impls.push(pattern.into());
+        self
+    }
+
+    /// Only matches members of impl blocks which implement no trait.
+    ///
+    /// Combined with [`in_impl`](Self::in_impl), members of either kind of block match.
+    /// Like `in_impl`, it needs [`member`](Self::member) to match anything.
+    pub fn inherent(mut self) -> Self {
+        self.inherent = true;
+        self
+    }
+
+    /// Finds the matches within the document of the item at `path`.
+    pub fn matches<'a>(&self, path: &str, document: &'a Document) -> Vec<QueryMatch<'a>> {
+        self.matches_at(path, &[path], document)
+    }
+
+    /// Matches the item at `path`, which may also be reached through `paths`.
+    fn matches_at<'a>(&self, path: &str, paths: &[&str], document: &'a Document) -> Vec<QueryMatch<'a>> {
+        let kind = item_kind(document.kind());
+        if !self.kinds.is_empty() && !self.kinds.contains(&kind) {
+            return Vec::new();
+        }
+        if !self.implements.iter().all(|pattern| implements(document, pattern)) {
+            return Vec::new();
+        }
+
+        if self.members.is_empty() {
+            let is_match = self.paths_match(paths, None)
+                && self.declaration_matches(document.declaration())
+                && self.marks_match(&[document.mark()])
+                && self.in_impls.is_empty()
+                && !self.inherent;
+            return if is_match {
+                vec![QueryMatch {
+                    path: path.to_owned(),
+                    kind,
+                    document,
+                    member: None,
+                }]
+            } else {
+                Vec::new()
+            };
+        }
+
+        members(document)
+            .into_iter()
+            .filter(|member| self.members.contains(&member.kind))
+            .filter_map(|member| {
+                let name = member_name(member.item.declaration());
+                let is_match = self.paths_match(paths, Some(name))
+                    && self.declaration_matches(Some(member.item.declaration()))
+                    && self.marks_match(&[document.mark(), member.item.mark()])
+                    && self.impl_matches(member.implementation);
+                if !is_match {
+                    return None;
+                }
+                Some(QueryMatch {
+                    path: format!("{}::{}", path, name),
+                    kind,
+                    document,
+                    member: Some(member),
+                })
+            })
+            .collect()
+    }
+
+    fn paths_match(&self, paths: &[&str], member: Option<&str>) -> bool {
+        self.paths.is_empty()
+            || paths.iter().any(|path| {
+                let path = match member {
+                    Some(name) => format!("{}::{}", path, name),
+                    None => path.to_string(),
+                };
+                self.paths.iter().any(|pattern| path_matches(pattern, &path))
+            })
+    }
+
+    fn declaration_matches(&self, declaration: Option<&Code>) -> bool {
+        if self.declarations.is_empty() {
+            return true;
+        }
+        let declaration = match declaration {
+            Some(declaration) => collapse_whitespace(declaration),
+            None => return false,
+        };
+        self.declarations.iter().all(|pattern| contains(&declaration, pattern))
+    }
+
+    /// Checks the marks of an item, or of a member and the item it belongs to.
+    fn marks_match(&self, marks: &[&Mark]) -> bool {
+        let is_set = |value: &str| !value.trim().is_empty();
+        let deprecated = marks.iter().any(|mark| is_set(mark.deprecated()));
+        let unstable = marks.iter().any(|mark| is_set(mark.stability()));
+        self.deprecated.is_none_or(|expected| expected == deprecated)
+            && self.unstable.is_none_or(|expected| expected == unstable)
+            && self
+                .features
+                .iter()
+                .all(|pattern| marks.iter().any(|mark| contains(mark.features(), pattern)))
+    }
+
+    fn impl_matches(&self, implementation: Option<&Implementation>) -> bool {
+        if self.in_impls.is_empty() && !self.inherent {
+            return true;
+        }
+        let implementation = match implementation {
+            Some(implementation) => implementation,
+            None => return false,
+        };
+        match implemented_trait(implementation.impl_code()) {
+            Some(name) => self.in_impls.iter().any(|pattern| trait_matches(pattern, &name)),
+            None => self.inherent,
+        }
+    }
+}
+
+impl Member<'_> {
+    pub fn kind(&self) -> MemberKind {
+        self.kind
+    }
+
+    pub fn item(&self) -> &SimpleItem {
+        self.item
+    }
+
+    /// The impl block the member belongs to, unless it is a field, variant or
+    /// part of a trait's own declaration.
+    pub fn implementation(&self) -> Option<&Implementation> {
+        self.implementation
+    }
+
+    /// Name of the member, as declared.
+    pub fn name(&self) -> &str {
+        member_name(self.item.declaration())
+    }
+}
+
+impl<'a> QueryMatch<'a> {
+    /// Path of the item, or of the item a member belongs to followed by its name.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Kind of the item, or of the item a member belongs to.
+    pub fn kind(&self) -> ItemKind {
+        self.kind
+    }
+
+    /// Document of the item, or of the item a member belongs to.
+    pub fn document(&self) -> &'a Document {
+        self.document
+    }
+
+    pub fn member(&self) -> Option<&Member<'a>> {
+        self.member.as_ref()
+    }
+
+    pub fn declaration(&self) -> Option<&'a Code> {
+        match self.member {
+            Some(member) => Some(member.item.declaration()),
+            None => self.document.declaration(),
+        }
+    }
+
+    pub fn mark(&self) -> &'a Mark {
+        match self.member {
+            Some(member) => member.item.mark(),
+            None => self.document.mark(),
+        }
+    }
+}
+
+impl CrateIndex {
+    /// Finds the items and members matching `query`, sorted by the path of their item.
+    ///
+    /// Items are also matched by the paths they are re-exported at, but are
+    /// reported at the path they were first found at.
+    pub fn query(&self, query: &Query) -> Vec<QueryMatch<'_>> {
+        let mut aliases: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (alias, path) in self.aliases() {
+            aliases.entry(path).or_default().push(alias);
+        }
+        self.iter()
+            .flat_map(|(path, item)| {
+                let mut paths = vec![path];
+                paths.extend(aliases.get(path).into_iter().flatten());
+                query.matches_at(path, &paths, item.document())
+            })
+            .collect()
+    }
+}
+
+fn item_kind(kind: &DocumentKind) -> ItemKind {
+    match kind {
+        DocumentKind::Crate(_) | DocumentKind::Module(_) => ItemKind::Module,
+        DocumentKind::Struct(_) => ItemKind::Struct,
+        DocumentKind::Enum(_) => ItemKind::Enum,
+        DocumentKind::Constant(_) => ItemKind::Constant,
+        DocumentKind::Function(_) => ItemKind::Function,
+        DocumentKind::Trait(_) => ItemKind::Trait,
+        DocumentKind::Macro(_) => ItemKind::Macro,
+        DocumentKind::Attribute(_) => ItemKind::Attribute,
+        DocumentKind::Type(_) => ItemKind::Type,
+        DocumentKind::Primitive(_) => ItemKind::Primitive,
+        DocumentKind::Keyword => ItemKind::Keyword,
+    }
+}
+
+/// Every member of an item, in the order rustdoc lists them.
+fn members(document: &Document) -> Vec<Member<'_>> {
+    let mut members = Vec::new();
+    let impls: Vec<&Implementation> = match document.kind() {
+        DocumentKind::Struct(item) => {
+            members.extend(simple_members(MemberKind::Field, item.fields()));
+            item.methods().iter().chain(all_trait_impls(item)).collect()
+        }
+        DocumentKind::Enum(item) => {
+            members.extend(simple_members(MemberKind::Variant, item.variants()));
+            all_trait_impls(item).collect()
+        }
+        DocumentKind::Primitive(item) => item.methods().iter().chain(all_trait_impls(item)).collect(),
+        DocumentKind::Trait(item) => {
+            members.extend(simple_members(MemberKind::AssociatedType, item.associated_types()));
+            members.extend(simple_members(MemberKind::Method, item.required_methods()));
+            Vec::new()
+        }
+        _ => Vec::new(),
+    };
+    for implementation in impls {
+        for (kind, items) in [
+            (MemberKind::AssociatedType, implementation.associated_types()),
+            (MemberKind::Method, implementation.methods()),
+        ] {
+            members.extend(items.iter().map(|item| Member {
+                kind,
+                item,
+                implementation: Some(implementation),
+            }));
+        }
+    }
+    members
+}
+
+fn simple_members(kind: MemberKind, items: &[SimpleItem]) -> impl Iterator<Item = Member<'_>> {
+    items.iter().map(move |item| Member {
+        kind,
+        item,
+        implementation: None,
+    })
+}
+
+fn all_trait_impls(item: &impl Implementable) -> impl Iterator<Item = &Implementation> {
+    item.trait_impls().iter().chain(item.auto_impls()).chain(item.blanket_impls())
+}
+
+fn implements(document: &Document, pattern: &str) -> bool {
+    let impls: Vec<&Implementation> = match document.kind() {
+        DocumentKind::Struct(item) => all_trait_impls(item).collect(),
+        DocumentKind::Enum(item) => all_trait_impls(item).collect(),
+        DocumentKind::Primitive(item) => all_trait_impls(item).collect(),
+        _ => Vec::new(),
+    };
+    impls
+        .into_iter()
+        .filter_map(|implementation| implemented_trait(implementation.impl_code()))
+        .any(|name| trait_matches(pattern, &name))
+}
+
+/// The trait an impl block implements, without generic arguments, or `None` for inherent impls.
+///
+/// Negative impls keep their `!`, so that `!Send` is never taken for `Send`.
+fn implemented_trait(impl_code: &str) -> Option<String> {
+    let header = collapse_whitespace(impl_code);
+    let header = header.strip_prefix("unsafe ").unwrap_or(&header);
+    let mut rest = header.strip_prefix("impl")?;
+    if rest.starts_with('<') {
+        let mut depth = 0;
+        let mut previous = ' ';
+        let end = rest.char_indices().find_map(|(i, c)| {
+            match c {
+                '<' => depth += 1,
+                // the arrow of a bound such as `F: Fn() -> R` closes nothing
+                '>' if previous != '-' => depth -= 1,
+                _ => {}
+            }
+            previous = c;
+            (depth == 0).then_some(i + 1)
+        })?;
+        rest = &rest[end..];
+    }
+    let (name, _) = rest.trim_start().split_once(" for ")?;
+    Some(name.split('<').next().unwrap_or(name).trim().to_owned())
+}
+
+/// Whether `pattern` names the trait, in full or by its last path segment.
+fn trait_matches(pattern: &str, name: &str) -> bool {
+    let last = name.rsplit("::").next().unwrap_or(name);
+    segment_matches(pattern, name) || segment_matches(pattern, last)
+}
+
+/// The name a field, variant, method or associated item is declared with.
+fn member_name(declaration: &str) -> &str {
+    let mut rest = declaration.trim_start();
+    loop {
+        let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+        let (word, after) = rest.split_at(end);
+        rest = match word {
+            "pub" => match after.strip_prefix('(') {
+                Some(scope) => scope.split_once(')').map_or("", |(_, after)| after),
+                None => after,
+            },
+            "const" | "async" | "unsafe" | "extern" | "fn" | "type" | "default" => after,
+            // the ABI of `extern "C" fn`
+            "" if after.starts_with('"') => after[1..].split_once('"').map_or("", |(_, after)| after),
+            _ => return word,
+        }
+        .trim_start();
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Whether `pattern`, in which `*` matches anything, occurs anywhere in `text`.
+fn contains(text: &str, pattern: &str) -> bool {
+    segment_matches(&format!("*{}*", pattern), text)
+}
@@ -0,0 +1,46 @@
+use std::future::Future;
+use std::panic;
+use std::sync::Mutex;
+use tokio::runtime::{Builder, Handle, Runtime};
+use super::Result;
+
+/// Runs futures which need a tokio reactor, such as reqwest requests, from any executor.
+///
+/// Inside a tokio runtime the future is simply awaited. Elsewhere it is
+/// spawned onto a small runtime owned by the client, created on first use,
+/// and only its result crosses back to the caller's executor.
+#[derive(Default)]
+pub(crate) struct BackgroundRuntime {
+    runtime: Mutex<Option<Runtime>>,
+}
+
+impl BackgroundRuntime {
+    pub async fn run<F, T>(&self, future: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>> + Send + 'static,
+        T: Send + 'static,
+    {
+        if Handle::try_current().is_ok() {
+            return future.await;
+        }
+        let task = {
+            let mut runtime = self.runtime.lock().unwrap();
+            if runtime.is_none() {
+                *runtime = Some(
+                    Builder::new()
+                        .threaded_scheduler()
+                        .core_threads(1)
+                        .thread_name("paradocs-http")
+                        .enable_all()
+                        .build()?,
+                );
+            }
+            runtime.as_ref().unwrap().spawn(future)
+        };
+        match task.await {
+            Ok(result) => result,
+            Err(error) if error.is_panic() => panic::resume_unwind(error.into_panic()),
+            Err(error) => panic!("request task was cancelled: {}", error),
+        }
+    }
+}
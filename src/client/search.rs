@@ -0,0 +1,186 @@
+use std::sync::Arc;
+use kuchiki::traits::TendrilSink;
+use reqwest::Url;
+use super::{DocsClient, Error, ItemPath, Page, Result, LATEST_VERSION, normalize_crate_name};
+use crate::element::{Document, ItemKind};
+
+/// An item listed on a crate's "all items" page.
+#[derive(Debug, Clone)]
+pub struct IndexEntry {
+    path: ItemPath,
+    kind: ItemKind,
+    url: String,
+}
+
+/// Every item of one crate, as listed on its "all items" page.
+#[derive(Debug, Clone)]
+pub struct ItemIndex {
+    crate_name: String,
+    root_url: String,
+    entries: Vec<IndexEntry>,
+}
+
+impl IndexEntry {
+    /// Full path of the item, starting with the crate name.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn kind(&self) -> ItemKind {
+        self.kind
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Last segment of the path.
+    pub fn name(&self) -> &str {
+        self.path.rsplit("::").next().unwrap_or(&self.path)
+    }
+}
+
+impl ItemIndex {
+    pub fn crate_name(&self) -> &str {
+        &self.crate_name
+    }
+
+    /// URL of the crate's root module.
+    pub fn root_url(&self) -> &str {
+        &self.root_url
+    }
+
+    pub fn entries(&self) -> &[IndexEntry] {
+        &self.entries
+    }
+
+    pub fn get(&self, path: &str) -> Option<&IndexEntry> {
+        self.entries.iter().find(|entry| entry.path == path)
+    }
+
+    /// Finds items whose path contains `query`, ignoring case.
+    ///
+    /// Items named exactly `query` come first, then items whose name starts
+    /// with it, then any other match, shorter paths first.
+    pub fn search(&self, query: &str) -> Vec<&IndexEntry> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let mut found: Vec<(usize, &IndexEntry)> = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let path = entry.path.to_lowercase();
+                let name = entry.name().to_lowercase();
+                let rank = if name == query || path == query || path.ends_with(&format!("::{}", query)) {
+                    0
+                } else if name.starts_with(&query) {
+                    1
+                } else if name.contains(&query) {
+                    2
+                } else if path.contains(&query) {
+                    3
+                } else {
+                    return None;
+                };
+                Some((rank, entry))
+            })
+            .collect();
+        found.sort_by(|(a_rank, a), (b_rank, b)| {
+            a_rank
+                .cmp(b_rank)
+                .then(a.path.len().cmp(&b.path.len()))
+                .then(a.path.cmp(&b.path))
+        });
+        found.into_iter().map(|(_, entry)| entry).collect()
+    }
+}
+
+impl DocsClient {
+    /// Lists every item of the latest version of `crate_name`.
+    pub async fn item_index(&self, crate_name: &str) -> Result<ItemIndex> {
+        self.item_index_version(crate_name, LATEST_VERSION).await
+    }
+
+    pub async fn item_index_version(&self, crate_name: &str, version: &str) -> Result<ItemIndex> {
+        let crate_name = normalize_crate_name(crate_name);
+        let package_name = self
+            .resolve_package(&crate_name)
+            .await
+            .ok_or_else(|| Error::PackageNotFound(crate_name.clone()))?;
+        let all_url = self.crate_url(&package_name, version, &crate_name) + "/all.html";
+        // links are resolved against the final URL, pinning them to the version which was listed
+        let Page { url, body, .. } = self.get_page(&all_url).await?;
+        let root_url = url.join("index.html").map_err(|_| Error::InvalidUrl(url.to_string()))?;
+        let entries = parse_all_items(&body, &crate_name, &url);
+        Ok(ItemIndex {
+            crate_name,
+            root_url: root_url.to_string(),
+            entries,
+        })
+    }
+
+    /// Finds items of `crate_name` matching `query`, as ranked by [`ItemIndex::search`].
+    pub async fn search(&self, crate_name: &str, query: &str) -> Result<Vec<IndexEntry>> {
+        let index = self.item_index(crate_name).await?;
+        Ok(index.search(query).into_iter().cloned().collect())
+    }
+
+    /// Loads the document at `url`, such as a link found in another document.
+    ///
+    /// Also returns the URL the page was finally served from, which relative
+    /// links in the document start from.
+    pub async fn get_document_url(&self, url: &str) -> Result<(String, Arc<Document>)> {
+        let (url, document) = self.fetch_document(url).await?;
+        Ok((url.to_string(), Arc::new(document)))
+    }
+}
+
+fn parse_all_items(body: &str, crate_name: &str, page_url: &Url) -> Vec<IndexEntry> {
+    let dom = kuchiki::parse_html().one(body);
+    let mut entries = Vec::new();
+    let lists = match dom.select("ul.docblock") {
+        Ok(lists) => lists,
+        Err(_) => return entries,
+    };
+    for list in lists {
+        let class = list.attributes.borrow().get("class").unwrap_or_default().to_owned();
+        let kind = match class.split_ascii_whitespace().find_map(section_kind) {
+            Some(kind) => kind,
+            None => continue,
+        };
+        let links = match list.as_node().select("li > a") {
+            Ok(links) => links,
+            Err(_) => continue,
+        };
+        for link in links {
+            let url = match link.attributes.borrow().get("href").map(|href| page_url.join(href)) {
+                Some(Ok(url)) => url,
+                _ => continue,
+            };
+            entries.push(IndexEntry {
+                path: format!("{}::{}", crate_name, link.text_contents().trim()),
+                kind,
+                url: url.to_string(),
+            });
+        }
+    }
+    entries
+}
+
+fn section_kind(class: &str) -> Option<ItemKind> {
+    match class {
+        "structs" | "unions" => Some(ItemKind::Struct),
+        "enums" => Some(ItemKind::Enum),
+        "traits" | "traitaliases" | "trait-aliases" => Some(ItemKind::Trait),
+        "macros" | "derives" => Some(ItemKind::Macro),
+        "attributes" => Some(ItemKind::Attribute),
+        "functions" => Some(ItemKind::Function),
+        "typedefs" | "types" => Some(ItemKind::Type),
+        "constants" | "statics" => Some(ItemKind::Constant),
+        "primitives" => Some(ItemKind::Primitive),
+        "keywords" => Some(ItemKind::Keyword),
+        _ => None,
+    }
+}
@@ -1,6 +1,9 @@
 use super::*;
+use kuchiki::traits::TendrilSink;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", content = "item", rename_all = "snake_case"))]
 pub enum DocumentKind {
     Crate(Module),
     Module(Module),
@@ -16,57 +19,173 @@ pub enum DocumentKind {
     Keyword,
 }
 
-#[derive(Debug)]
+/// A parsed rustdoc page.
+///
+/// With the `serde` feature, documents serialize to JSON like the example below.
+#[cfg_attr(
+    feature = "serde",
+    doc = "The format is described by [`DOCUMENT_SCHEMA`] and versioned by [`FORMAT_VERSION`].",
+    doc = "Data written by older releases can be brought up to date with [`migrate`]."
+)]
+///
+/// ```json
+/// {
+///   "format_version": 1,
+///   "title": "Function tokio::spawn",
+///   "mark": { "stability": "", "features": "", "deprecated": "" },
+///   "description": [
+///     {
+///       "title": null,
+///       "content": [
+///         { "type": "text", "content": { "components": [
+///           { "text": "Spawns a new task.", "code": false, "italic": false, "bold": false }
+///         ] } },
+///         { "type": "code", "content": "tokio::spawn(async {});" }
+///       ]
+///     }
+///   ],
+///   "kind": { "kind": "function", "item": "pub fn spawn<T>(task: T) -> JoinHandle<T::Output>" }
+/// }
+/// ```
+///
+/// - `kind.kind` is the snake_case name of a [`DocumentKind`] variant, and
+///   `kind.item` holds its content: a declaration string, or an object for
+///   modules, structs, enums, traits and primitives. It is absent for keywords.
+/// - Paragraphs are tagged by `type` (`text`, `list`, `code`, `invalid_code`
+///   or `sub_section`) with their value in `content`.
+/// - Text components only carry `link` when they are links.
+/// - Other object fields are named after the accessor which returns them,
+///   such as `sub_items`, `trait_impls` or `associated_types`.
+#[derive(Debug, Clone)]
 pub struct Document {
+    title: String,
+    pub(super) mark: Mark,
+    pub(super) description: Vec<Section>,
+    pub(super) kind: DocumentKind,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct VersionedDocumentRef<'a> {
+    format_version: u32,
+    title: &'a str,
+    mark: &'a Mark,
+    description: &'a [Section],
+    kind: &'a DocumentKind,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct VersionedDocument {
+    format_version: Option<u32>,
     title: String,
     mark: Mark,
     description: Vec<Section>,
     kind: DocumentKind,
 }
 
-#[derive(Debug, Default)]
+#[cfg(feature = "serde")]
+impl serde::Serialize for Document {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let document = VersionedDocumentRef {
+            format_version: FORMAT_VERSION,
+            title: &self.title,
+            mark: &self.mark,
+            description: &self.description,
+            kind: &self.kind,
+        };
+        serde::Serialize::serialize(&document, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Document {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let document = VersionedDocument::deserialize(deserializer)?;
+        match document.format_version {
+            Some(FORMAT_VERSION) => Ok(Document {
+                title: document.title,
+                mark: document.mark,
+                description: document.description,
+                kind: document.kind,
+            }),
+            Some(version) => Err(serde::de::Error::custom(format_args!(
+                "unsupported format version {}, expected {}",
+                version, FORMAT_VERSION
+            ))),
+            None => Err(serde::de::Error::missing_field("format_version")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Module {
-    re_exports: Vec<ExportItem>,
-    sub_item: Vec<SummarySection>,
+    pub(super) re_exports: Vec<ExportItem>,
+    #[cfg_attr(feature = "serde", serde(rename = "sub_items"))]
+    pub(super) sub_item: Vec<SummarySection>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Struct {
-    declaration: Code,
-    fields: Vec<SimpleItem>,
-    methods: Vec<Implementation>,
-    trait_impl: Vec<Implementation>,
-    auto_impl: Vec<Implementation>,
-    blanket: Vec<Implementation>,
+    pub(super) declaration: Code,
+    pub(super) fields: Vec<SimpleItem>,
+    pub(super) methods: Vec<Implementation>,
+    #[cfg_attr(feature = "serde", serde(rename = "trait_impls"))]
+    pub(super) trait_impl: Vec<Implementation>,
+    #[cfg_attr(feature = "serde", serde(rename = "auto_impls"))]
+    pub(super) auto_impl: Vec<Implementation>,
+    #[cfg_attr(feature = "serde", serde(rename = "blanket_impls"))]
+    pub(super) blanket: Vec<Implementation>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Trait {
-    declaration: Code,
-    assoc_types: Vec<SimpleItem>,
-    required: Vec<SimpleItem>,
-    foreigns: Vec<Implementation>,
-    implementors: Vec<Implementation>,
+    pub(super) declaration: Code,
+    #[cfg_attr(feature = "serde", serde(rename = "associated_types"))]
+    pub(super) assoc_types: Vec<SimpleItem>,
+    #[cfg_attr(feature = "serde", serde(rename = "required_methods"))]
+    pub(super) required: Vec<SimpleItem>,
+    #[cfg_attr(feature = "serde", serde(rename = "foreign_implementations"))]
+    pub(super) foreigns: Vec<Implementation>,
+    pub(super) implementors: Vec<Implementation>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Enum {
-    declaration: Code,
-    variants: Vec<SimpleItem>,
-    trait_impl: Vec<Implementation>,
-    auto_impl: Vec<Implementation>,
-    blanket: Vec<Implementation>,
+    pub(super) declaration: Code,
+    pub(super) variants: Vec<SimpleItem>,
+    #[cfg_attr(feature = "serde", serde(rename = "trait_impls"))]
+    pub(super) trait_impl: Vec<Implementation>,
+    #[cfg_attr(feature = "serde", serde(rename = "auto_impls"))]
+    pub(super) auto_impl: Vec<Implementation>,
+    #[cfg_attr(feature = "serde", serde(rename = "blanket_impls"))]
+    pub(super) blanket: Vec<Implementation>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Primitive {
-    methods: Vec<Implementation>,
-    trait_impl: Vec<Implementation>,
-    auto_impl: Vec<Implementation>,
-    blanket: Vec<Implementation>,
+    pub(super) methods: Vec<Implementation>,
+    #[cfg_attr(feature = "serde", serde(rename = "trait_impls"))]
+    pub(super) trait_impl: Vec<Implementation>,
+    #[cfg_attr(feature = "serde", serde(rename = "auto_impls"))]
+    pub(super) auto_impl: Vec<Implementation>,
+    #[cfg_attr(feature = "serde", serde(rename = "blanket_impls"))]
+    pub(super) blanket: Vec<Implementation>,
 }
 
 impl Document {
+    /// Parses a rustdoc page which has already been downloaded.
+    pub fn from_html(html: &str) -> Option<Self> {
+        let dom = kuchiki::parse_html().one(html);
+        parse_document(&dom)
+    }
+
     pub fn title(&self) -> &str {
         &self.title
     }
@@ -74,6 +193,21 @@ impl Document {
     pub fn kind(&self) -> &DocumentKind {
         &self.kind
     }
+
+    /// Declaration of the item, unless it is a module, primitive or keyword.
+    pub fn declaration(&self) -> Option<&Code> {
+        match &self.kind {
+            DocumentKind::Struct(item) => Some(item.declaration()),
+            DocumentKind::Enum(item) => Some(item.declaration()),
+            DocumentKind::Trait(item) => Some(item.declaration()),
+            DocumentKind::Constant(code)
+            | DocumentKind::Function(code)
+            | DocumentKind::Macro(code)
+            | DocumentKind::Attribute(code)
+            | DocumentKind::Type(code) => Some(code),
+            _ => None,
+        }
+    }
 }
 
 impl Described for Document {
@@ -180,6 +314,12 @@ impl Implementable for Enum {
     }
 }
 
+impl Primitive {
+    pub fn methods(&self) -> &[Implementation] {
+        &self.methods
+    }
+}
+
 impl Implementable for Primitive {
     fn trait_impls(&self) -> &[Implementation] {
         &self.trait_impl
@@ -274,7 +414,7 @@ pub(crate) fn parse_document(input: &NodeRef) -> Option<Document> {
                 kind: DocumentKind::Keyword
             })
         }
-        _ => return None
+        _ => None
     }
 }
 
@@ -1,4 +1,5 @@
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mark {
     stability: String,
     features: String,
@@ -17,6 +18,18 @@ impl Mark {
     pub fn deprecated(&self) -> &str {
         &self.deprecated
     }
+
+    pub fn set_stability(&mut self, stability: impl Into<String>) {
+        self.stability = stability.into();
+    }
+
+    pub fn set_features(&mut self, features: impl Into<String>) {
+        self.features = features.into();
+    }
+
+    pub fn set_deprecated(&mut self, deprecated: impl Into<String>) {
+        self.deprecated = deprecated.into();
+    }
 }
 
 use kuchiki::NodeRef;
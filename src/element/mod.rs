@@ -4,6 +4,9 @@ mod summary;
 mod mark;
 mod document;
 mod implementation;
+mod visit;
+#[cfg(feature = "serde")]
+mod schema;
 
 pub use text::*;
 pub use reexport::*;
@@ -11,10 +14,14 @@ pub use summary::*;
 pub use mark::*;
 pub use document::*;
 pub use implementation::*;
+pub use visit::*;
+#[cfg(feature = "serde")]
+pub use schema::*;
 
 pub type Code = String;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SimpleItem {
     declaration: Code,
     mark: Mark,
@@ -53,6 +60,12 @@ impl Described for SimpleItem {
     }
 }
 
+impl Declared for SimpleItem {
+    fn declaration(&self) -> &Code {
+        &self.declaration
+    }
+}
+
 impl Marked for SimpleItem {
     fn mark(&self) -> &Mark {
         &self.mark
@@ -61,30 +74,6 @@ impl Marked for SimpleItem {
 
 use kuchiki::{NodeRef, NodeData, iter::NodeEdge};
 use html5ever::local_name;
-pub(crate) fn parse_simple_item_forward(head: NodeRef) -> Option<(Option<NodeRef>, SimpleItem)> {
-    let declaration = parse_generic_code(&head);
-    let head = head.next_sibling().and_then(skip_uninformative);
-
-    let (head, mark) = if let Some(head) = head {
-        parse_marks_forward(head)
-    } else {
-        (None, Mark::default())
-    };
-
-    let head = head.and_then(skip_uninformative);
-    let (head, description) = if let Some(head) = head {
-        (head.next_sibling(), parse_docblock(&head)?)
-    } else {
-        (None, Vec::new())
-    };
-
-    Some((head, SimpleItem {
-        declaration,
-        mark,
-        description
-    }))
-}
-
 pub(crate) fn parse_generic_code(pre: &NodeRef) -> Code {
     let mut output = Code::new();
     for edge in pre.traverse() {
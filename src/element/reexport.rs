@@ -1,7 +1,8 @@
 use super::{Declared, Code};
 
-#[derive(Debug)]
-pub struct ExportItem(Code);
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExportItem(pub(super) Code);
 
 impl Declared for ExportItem {
     fn declaration(&self) -> &Code {
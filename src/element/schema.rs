@@ -0,0 +1,84 @@
+use super::*;
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Version of the JSON format written by serializing a [`Document`].
+pub const FORMAT_VERSION: u32 = 1;
+
+/// JSON Schema (draft-07) of a serialized [`Document`].
+pub const DOCUMENT_SCHEMA: &str = include_str!("../../schema/document.schema.json");
+
+/// Upgrades one format version to the next, indexed by the older version.
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[
+    // Version 0 is the unversioned shape written before documents carried
+    // a `format_version` field; it has the same layout as version 1.
+    |_| {},
+];
+
+#[derive(Debug)]
+pub enum MigrationError {
+    /// The document was written by a newer paradocs.
+    UnsupportedVersion(u64),
+    /// The document does not match its format version.
+    Invalid(serde_json::Error),
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported format version {}, expected at most {}",
+                version, FORMAT_VERSION
+            ),
+            MigrationError::Invalid(e) => write!(f, "invalid document: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MigrationError::Invalid(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for MigrationError {
+    fn from(e: serde_json::Error) -> Self {
+        MigrationError::Invalid(e)
+    }
+}
+
+/// Reads a serialized document of any older format version into the current model.
+///
+/// Documents without `format_version` are treated as version 0.
+pub fn migrate(value: Value) -> Result<Document, MigrationError> {
+    let mut object = match value {
+        Value::Object(object) => object,
+        other => return Ok(serde_json::from_value(other)?),
+    };
+
+    let version = match object.get("format_version") {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| serde::de::Error::custom("format_version is not an unsigned integer"))
+            .map_err(MigrationError::Invalid)?,
+    };
+    if version > u64::from(FORMAT_VERSION) {
+        return Err(MigrationError::UnsupportedVersion(version));
+    }
+
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(&mut object);
+    }
+    object.insert("format_version".to_owned(), FORMAT_VERSION.into());
+    Ok(serde_json::from_value(Value::Object(object))?)
+}
+
+/// Like [`migrate`], reading the document from a JSON string.
+pub fn migrate_str(json: &str) -> Result<Document, MigrationError> {
+    migrate(serde_json::from_str(json)?)
+}
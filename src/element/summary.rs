@@ -1,13 +1,18 @@
 use super::{Mark, parse_marks_forward, Text, parse_text};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ItemSummary {
     name: String,
-    attribute: Mark,
-    summary: Text,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    link: Option<String>,
+    pub(super) attribute: Mark,
+    pub(super) summary: Text,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum ItemKind {
     Module,
     Struct,
@@ -22,10 +27,11 @@ pub enum ItemKind {
     Keyword,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SummarySection {
     item_type: ItemKind,
-    content: Vec<ItemSummary>,
+    pub(super) content: Vec<ItemSummary>,
 }
 
 impl ItemSummary {
@@ -33,6 +39,10 @@ impl ItemSummary {
         &self.name
     }
 
+    pub fn link(&self) -> Option<&str> {
+        self.link.as_deref()
+    }
+
     pub fn attribute(&self) -> &Mark {
         &self.attribute
     }
@@ -40,6 +50,10 @@ impl ItemSummary {
     pub fn summary(&self) -> &Text {
         &self.summary
     }
+
+    pub fn set_link(&mut self, link: Option<String>) {
+        self.link = link;
+    }
 }
 
 impl SummarySection {
@@ -80,11 +94,17 @@ pub(crate) fn parse_summary_forward(sub_item_header: NodeRef) -> Option<(Option<
         .unwrap()
         .flat_map(|tr| {
             let node = tr.as_node();
-            let name = node.first_child()?.text_contents();
+            let name_cell = node.first_child()?;
+            let name = name_cell.text_contents();
+            let link = name_cell
+                .select_first("a")
+                .ok()
+                .and_then(|a| a.attributes.borrow().get("href").map(str::to_owned));
             let short_docblock = node.last_child()?;
             let (attribute, summary) = parse_short_docblock(short_docblock)?;
             Some(ItemSummary {
                 name,
+                link,
                 attribute,
                 summary,
             })
@@ -1,6 +1,9 @@
 use super::Code;
+use std::fmt;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "content", rename_all = "snake_case"))]
 pub enum Paragraph {
     Text(Text),
     List(Vec<Text>),
@@ -9,25 +12,29 @@ pub enum Paragraph {
     SubSection(Box<Section>),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Section {
-    title: Option<Text>,
-    content: Vec<Paragraph>,
+    pub(super) title: Option<Text>,
+    pub(super) content: Vec<Paragraph>,
 }
 
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextComponent {
     text: String,
     code: bool,
     italic: bool,
     bold: bool,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
     link: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Text {
-    components: Vec<TextComponent>,
+    pub(super) components: Vec<TextComponent>,
 }
 
 impl Section {
@@ -60,6 +67,14 @@ impl TextComponent {
     pub fn link(&self) -> Option<&str> {
         self.link.as_deref()
     }
+
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+    }
+
+    pub fn set_link(&mut self, link: Option<String>) {
+        self.link = link;
+    }
 }
 
 impl Text {
@@ -68,6 +83,13 @@ impl Text {
     }
 }
 
+/// Writes the text without any styling.
+impl fmt::Display for Text {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.components.iter().try_for_each(|component| f.write_str(&component.text))
+    }
+}
+
 use kuchiki::{NodeRef, NodeData, iter::NodeEdge};
 use html5ever::local_name;
 pub(crate) fn parse_text(node: &NodeRef) -> Text {
@@ -196,8 +218,7 @@ pub(crate) fn parse_docblock(docblock: &NodeRef) -> Option<Vec<Section>> {
                     previous_stage
                         .content
                         .push(Paragraph::SubSection(Box::new(Section { title, content })));
-                    while !stack.is_empty() {
-                        let mut parent_stage = stack.pop().unwrap();
+                    while let Some(mut parent_stage) = stack.pop() {
                         parent_stage
                             .content
                             .push(Paragraph::SubSection(Box::new(previous_stage)));
@@ -223,8 +244,7 @@ pub(crate) fn parse_docblock(docblock: &NodeRef) -> Option<Vec<Section>> {
         previous_stage
             .content
             .push(Paragraph::SubSection(Box::new(Section { title, content })));
-        while !stack.is_empty() {
-            let mut parent_stage = stack.pop().unwrap();
+        while let Some(mut parent_stage) = stack.pop() {
             parent_stage
                 .content
                 .push(Paragraph::SubSection(Box::new(previous_stage)));
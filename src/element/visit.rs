@@ -0,0 +1,485 @@
+use super::*;
+
+/// Walks over a document, one method per part of the model.
+///
+/// Every method defaults to the `walk_*` function of the same name, which
+/// visits the children of its node. Override the methods for the parts of
+/// interest, and call the `walk_*` function from them to keep descending.
+///
+/// ```
+/// use paradocs::element::{walk_section, Section, Visitor};
+///
+/// /// Collects the titles of every section, nested ones included.
+/// #[derive(Default)]
+/// struct Titles(Vec<String>);
+///
+/// impl<'a> Visitor<'a> for Titles {
+///     fn visit_section(&mut self, section: &'a Section) {
+///         if let Some(title) = section.title() {
+///             self.0.push(title.to_string());
+///         }
+///         walk_section(self, section);
+///     }
+/// }
+/// ```
+pub trait Visitor<'a> {
+    fn visit_document(&mut self, document: &'a Document) {
+        walk_document(self, document);
+    }
+
+    fn visit_document_kind(&mut self, kind: &'a DocumentKind) {
+        walk_document_kind(self, kind);
+    }
+
+    fn visit_module(&mut self, module: &'a Module) {
+        walk_module(self, module);
+    }
+
+    fn visit_export_item(&mut self, item: &'a ExportItem) {
+        walk_export_item(self, item);
+    }
+
+    fn visit_summary_section(&mut self, section: &'a SummarySection) {
+        walk_summary_section(self, section);
+    }
+
+    fn visit_item_summary(&mut self, summary: &'a ItemSummary) {
+        walk_item_summary(self, summary);
+    }
+
+    fn visit_struct(&mut self, item: &'a Struct) {
+        walk_struct(self, item);
+    }
+
+    fn visit_enum(&mut self, item: &'a Enum) {
+        walk_enum(self, item);
+    }
+
+    fn visit_trait(&mut self, item: &'a Trait) {
+        walk_trait(self, item);
+    }
+
+    fn visit_primitive(&mut self, item: &'a Primitive) {
+        walk_primitive(self, item);
+    }
+
+    /// Inherent impls, and by default every other kind of impl.
+    fn visit_implementation(&mut self, implementation: &'a Implementation) {
+        walk_implementation(self, implementation);
+    }
+
+    /// Trait impls of a type, and impls of a trait for other types.
+    fn visit_trait_impl(&mut self, implementation: &'a Implementation) {
+        self.visit_implementation(implementation);
+    }
+
+    fn visit_auto_impl(&mut self, implementation: &'a Implementation) {
+        self.visit_implementation(implementation);
+    }
+
+    fn visit_blanket_impl(&mut self, implementation: &'a Implementation) {
+        self.visit_implementation(implementation);
+    }
+
+    /// Fields, variants, methods and associated types.
+    fn visit_simple_item(&mut self, item: &'a SimpleItem) {
+        walk_simple_item(self, item);
+    }
+
+    /// Declarations of items, headers of impl blocks and re-exports.
+    fn visit_declaration(&mut self, _code: &'a Code) {}
+
+    fn visit_mark(&mut self, _mark: &'a Mark) {}
+
+    fn visit_section(&mut self, section: &'a Section) {
+        walk_section(self, section);
+    }
+
+    fn visit_paragraph(&mut self, paragraph: &'a Paragraph) {
+        walk_paragraph(self, paragraph);
+    }
+
+    /// Code blocks of descriptions, whether they compile or not.
+    fn visit_code(&mut self, _code: &'a Code) {}
+
+    fn visit_text(&mut self, text: &'a Text) {
+        walk_text(self, text);
+    }
+
+    fn visit_text_component(&mut self, _component: &'a TextComponent) {}
+}
+
+pub fn walk_document<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, document: &'a Document) {
+    visitor.visit_mark(&document.mark);
+    for section in &document.description {
+        visitor.visit_section(section);
+    }
+    visitor.visit_document_kind(&document.kind);
+}
+
+pub fn walk_document_kind<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, kind: &'a DocumentKind) {
+    match kind {
+        DocumentKind::Crate(module) | DocumentKind::Module(module) => visitor.visit_module(module),
+        DocumentKind::Struct(item) => visitor.visit_struct(item),
+        DocumentKind::Enum(item) => visitor.visit_enum(item),
+        DocumentKind::Trait(item) => visitor.visit_trait(item),
+        DocumentKind::Primitive(item) => visitor.visit_primitive(item),
+        DocumentKind::Constant(code)
+        | DocumentKind::Function(code)
+        | DocumentKind::Macro(code)
+        | DocumentKind::Attribute(code)
+        | DocumentKind::Type(code) => visitor.visit_declaration(code),
+        DocumentKind::Keyword => {}
+    }
+}
+
+pub fn walk_module<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, module: &'a Module) {
+    for item in &module.re_exports {
+        visitor.visit_export_item(item);
+    }
+    for section in &module.sub_item {
+        visitor.visit_summary_section(section);
+    }
+}
+
+pub fn walk_export_item<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, item: &'a ExportItem) {
+    visitor.visit_declaration(&item.0);
+}
+
+pub fn walk_summary_section<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, section: &'a SummarySection) {
+    for summary in &section.content {
+        visitor.visit_item_summary(summary);
+    }
+}
+
+pub fn walk_item_summary<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, summary: &'a ItemSummary) {
+    visitor.visit_mark(&summary.attribute);
+    visitor.visit_text(&summary.summary);
+}
+
+pub fn walk_struct<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, item: &'a Struct) {
+    visitor.visit_declaration(&item.declaration);
+    for field in &item.fields {
+        visitor.visit_simple_item(field);
+    }
+    for implementation in &item.methods {
+        visitor.visit_implementation(implementation);
+    }
+    walk_impls(visitor, &item.trait_impl, &item.auto_impl, &item.blanket);
+}
+
+pub fn walk_enum<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, item: &'a Enum) {
+    visitor.visit_declaration(&item.declaration);
+    for variant in &item.variants {
+        visitor.visit_simple_item(variant);
+    }
+    walk_impls(visitor, &item.trait_impl, &item.auto_impl, &item.blanket);
+}
+
+pub fn walk_trait<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, item: &'a Trait) {
+    visitor.visit_declaration(&item.declaration);
+    for simple_item in item.assoc_types.iter().chain(&item.required) {
+        visitor.visit_simple_item(simple_item);
+    }
+    for implementation in item.foreigns.iter().chain(&item.implementors) {
+        visitor.visit_trait_impl(implementation);
+    }
+}
+
+pub fn walk_primitive<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, item: &'a Primitive) {
+    for implementation in &item.methods {
+        visitor.visit_implementation(implementation);
+    }
+    walk_impls(visitor, &item.trait_impl, &item.auto_impl, &item.blanket);
+}
+
+fn walk_impls<'a, V: Visitor<'a> + ?Sized>(
+    visitor: &mut V,
+    trait_impls: &'a [Implementation],
+    auto_impls: &'a [Implementation],
+    blanket_impls: &'a [Implementation],
+) {
+    for implementation in trait_impls {
+        visitor.visit_trait_impl(implementation);
+    }
+    for implementation in auto_impls {
+        visitor.visit_auto_impl(implementation);
+    }
+    for implementation in blanket_impls {
+        visitor.visit_blanket_impl(implementation);
+    }
+}
+
+pub fn walk_implementation<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, implementation: &'a Implementation) {
+    visitor.visit_declaration(&implementation.impl_code);
+    for item in implementation.assoc_types.iter().chain(&implementation.methods) {
+        visitor.visit_simple_item(item);
+    }
+}
+
+pub fn walk_simple_item<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, item: &'a SimpleItem) {
+    visitor.visit_declaration(&item.declaration);
+    visitor.visit_mark(&item.mark);
+    for section in &item.description {
+        visitor.visit_section(section);
+    }
+}
+
+pub fn walk_section<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, section: &'a Section) {
+    if let Some(title) = &section.title {
+        visitor.visit_text(title);
+    }
+    for paragraph in &section.content {
+        visitor.visit_paragraph(paragraph);
+    }
+}
+
+pub fn walk_paragraph<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, paragraph: &'a Paragraph) {
+    match paragraph {
+        Paragraph::Text(text) => visitor.visit_text(text),
+        Paragraph::List(items) => {
+            for text in items {
+                visitor.visit_text(text);
+            }
+        }
+        Paragraph::Code(code) | Paragraph::InvalidCode(code) => visitor.visit_code(code),
+        Paragraph::SubSection(section) => visitor.visit_section(section),
+    }
+}
+
+pub fn walk_text<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, text: &'a Text) {
+    for component in &text.components {
+        visitor.visit_text_component(component);
+    }
+}
+
+/// Walks over a document like [`Visitor`], with mutable access to every part.
+pub trait VisitorMut {
+    fn visit_document_mut(&mut self, document: &mut Document) {
+        walk_document_mut(self, document);
+    }
+
+    fn visit_document_kind_mut(&mut self, kind: &mut DocumentKind) {
+        walk_document_kind_mut(self, kind);
+    }
+
+    fn visit_module_mut(&mut self, module: &mut Module) {
+        walk_module_mut(self, module);
+    }
+
+    fn visit_export_item_mut(&mut self, item: &mut ExportItem) {
+        walk_export_item_mut(self, item);
+    }
+
+    fn visit_summary_section_mut(&mut self, section: &mut SummarySection) {
+        walk_summary_section_mut(self, section);
+    }
+
+    fn visit_item_summary_mut(&mut self, summary: &mut ItemSummary) {
+        walk_item_summary_mut(self, summary);
+    }
+
+    fn visit_struct_mut(&mut self, item: &mut Struct) {
+        walk_struct_mut(self, item);
+    }
+
+    fn visit_enum_mut(&mut self, item: &mut Enum) {
+        walk_enum_mut(self, item);
+    }
+
+    fn visit_trait_mut(&mut self, item: &mut Trait) {
+        walk_trait_mut(self, item);
+    }
+
+    fn visit_primitive_mut(&mut self, item: &mut Primitive) {
+        walk_primitive_mut(self, item);
+    }
+
+    fn visit_implementation_mut(&mut self, implementation: &mut Implementation) {
+        walk_implementation_mut(self, implementation);
+    }
+
+    fn visit_trait_impl_mut(&mut self, implementation: &mut Implementation) {
+        self.visit_implementation_mut(implementation);
+    }
+
+    fn visit_auto_impl_mut(&mut self, implementation: &mut Implementation) {
+        self.visit_implementation_mut(implementation);
+    }
+
+    fn visit_blanket_impl_mut(&mut self, implementation: &mut Implementation) {
+        self.visit_implementation_mut(implementation);
+    }
+
+    fn visit_simple_item_mut(&mut self, item: &mut SimpleItem) {
+        walk_simple_item_mut(self, item);
+    }
+
+    fn visit_declaration_mut(&mut self, _code: &mut Code) {}
+
+    /// Marks can be rewritten through [`Mark::set_stability`],
+    /// [`Mark::set_features`] and [`Mark::set_deprecated`].
+    fn visit_mark_mut(&mut self, _mark: &mut Mark) {}
+
+    fn visit_section_mut(&mut self, section: &mut Section) {
+        walk_section_mut(self, section);
+    }
+
+    fn visit_paragraph_mut(&mut self, paragraph: &mut Paragraph) {
+        walk_paragraph_mut(self, paragraph);
+    }
+
+    fn visit_code_mut(&mut self, _code: &mut Code) {}
+
+    fn visit_text_mut(&mut self, text: &mut Text) {
+        walk_text_mut(self, text);
+    }
+
+    /// Components can be rewritten through [`TextComponent::set_text`] and
+    /// [`TextComponent::set_link`].
+    fn visit_text_component_mut(&mut self, _component: &mut TextComponent) {}
+}
+
+pub fn walk_document_mut<V: VisitorMut + ?Sized>(visitor: &mut V, document: &mut Document) {
+    visitor.visit_mark_mut(&mut document.mark);
+    for section in &mut document.description {
+        visitor.visit_section_mut(section);
+    }
+    visitor.visit_document_kind_mut(&mut document.kind);
+}
+
+pub fn walk_document_kind_mut<V: VisitorMut + ?Sized>(visitor: &mut V, kind: &mut DocumentKind) {
+    match kind {
+        DocumentKind::Crate(module) | DocumentKind::Module(module) => visitor.visit_module_mut(module),
+        DocumentKind::Struct(item) => visitor.visit_struct_mut(item),
+        DocumentKind::Enum(item) => visitor.visit_enum_mut(item),
+        DocumentKind::Trait(item) => visitor.visit_trait_mut(item),
+        DocumentKind::Primitive(item) => visitor.visit_primitive_mut(item),
+        DocumentKind::Constant(code)
+        | DocumentKind::Function(code)
+        | DocumentKind::Macro(code)
+        | DocumentKind::Attribute(code)
+        | DocumentKind::Type(code) => visitor.visit_declaration_mut(code),
+        DocumentKind::Keyword => {}
+    }
+}
+
+pub fn walk_module_mut<V: VisitorMut + ?Sized>(visitor: &mut V, module: &mut Module) {
+    for item in &mut module.re_exports {
+        visitor.visit_export_item_mut(item);
+    }
+    for section in &mut module.sub_item {
+        visitor.visit_summary_section_mut(section);
+    }
+}
+
+pub fn walk_export_item_mut<V: VisitorMut + ?Sized>(visitor: &mut V, item: &mut ExportItem) {
+    visitor.visit_declaration_mut(&mut item.0);
+}
+
+pub fn walk_summary_section_mut<V: VisitorMut + ?Sized>(visitor: &mut V, section: &mut SummarySection) {
+    for summary in &mut section.content {
+        visitor.visit_item_summary_mut(summary);
+    }
+}
+
+pub fn walk_item_summary_mut<V: VisitorMut + ?Sized>(visitor: &mut V, summary: &mut ItemSummary) {
+    visitor.visit_mark_mut(&mut summary.attribute);
+    visitor.visit_text_mut(&mut summary.summary);
+}
+
+pub fn walk_struct_mut<V: VisitorMut + ?Sized>(visitor: &mut V, item: &mut Struct) {
+    visitor.visit_declaration_mut(&mut item.declaration);
+    for field in &mut item.fields {
+        visitor.visit_simple_item_mut(field);
+    }
+    for implementation in &mut item.methods {
+        visitor.visit_implementation_mut(implementation);
+    }
+    walk_impls_mut(visitor, &mut item.trait_impl, &mut item.auto_impl, &mut item.blanket);
+}
+
+pub fn walk_enum_mut<V: VisitorMut + ?Sized>(visitor: &mut V, item: &mut Enum) {
+    visitor.visit_declaration_mut(&mut item.declaration);
+    for variant in &mut item.variants {
+        visitor.visit_simple_item_mut(variant);
+    }
+    walk_impls_mut(visitor, &mut item.trait_impl, &mut item.auto_impl, &mut item.blanket);
+}
+
+pub fn walk_trait_mut<V: VisitorMut + ?Sized>(visitor: &mut V, item: &mut Trait) {
+    visitor.visit_declaration_mut(&mut item.declaration);
+    for simple_item in item.assoc_types.iter_mut().chain(&mut item.required) {
+        visitor.visit_simple_item_mut(simple_item);
+    }
+    for implementation in item.foreigns.iter_mut().chain(&mut item.implementors) {
+        visitor.visit_trait_impl_mut(implementation);
+    }
+}
+
+pub fn walk_primitive_mut<V: VisitorMut + ?Sized>(visitor: &mut V, item: &mut Primitive) {
+    for implementation in &mut item.methods {
+        visitor.visit_implementation_mut(implementation);
+    }
+    walk_impls_mut(visitor, &mut item.trait_impl, &mut item.auto_impl, &mut item.blanket);
+}
+
+fn walk_impls_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    trait_impls: &mut [Implementation],
+    auto_impls: &mut [Implementation],
+    blanket_impls: &mut [Implementation],
+) {
+    for implementation in trait_impls {
+        visitor.visit_trait_impl_mut(implementation);
+    }
+    for implementation in auto_impls {
+        visitor.visit_auto_impl_mut(implementation);
+    }
+    for implementation in blanket_impls {
+        visitor.visit_blanket_impl_mut(implementation);
+    }
+}
+
+pub fn walk_implementation_mut<V: VisitorMut + ?Sized>(visitor: &mut V, implementation: &mut Implementation) {
+    visitor.visit_declaration_mut(&mut implementation.impl_code);
+    for item in implementation.assoc_types.iter_mut().chain(&mut implementation.methods) {
+        visitor.visit_simple_item_mut(item);
+    }
+}
+
+pub fn walk_simple_item_mut<V: VisitorMut + ?Sized>(visitor: &mut V, item: &mut SimpleItem) {
+    visitor.visit_declaration_mut(&mut item.declaration);
+    visitor.visit_mark_mut(&mut item.mark);
+    for section in &mut item.description {
+        visitor.visit_section_mut(section);
+    }
+}
+
+pub fn walk_section_mut<V: VisitorMut + ?Sized>(visitor: &mut V, section: &mut Section) {
+    if let Some(title) = &mut section.title {
+        visitor.visit_text_mut(title);
+    }
+    for paragraph in &mut section.content {
+        visitor.visit_paragraph_mut(paragraph);
+    }
+}
+
+pub fn walk_paragraph_mut<V: VisitorMut + ?Sized>(visitor: &mut V, paragraph: &mut Paragraph) {
+    match paragraph {
+        Paragraph::Text(text) => visitor.visit_text_mut(text),
+        Paragraph::List(items) => {
+            for text in items {
+                visitor.visit_text_mut(text);
+            }
+        }
+        Paragraph::Code(code) | Paragraph::InvalidCode(code) => visitor.visit_code_mut(code),
+        Paragraph::SubSection(section) => visitor.visit_section_mut(section),
+    }
+}
+
+pub fn walk_text_mut<V: VisitorMut + ?Sized>(visitor: &mut V, text: &mut Text) {
+    for component in &mut text.components {
+        visitor.visit_text_component_mut(component);
+    }
+}
@@ -0,0 +1,232 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use reqwest::Url;
+use rusqlite::{params, Connection};
+use super::html::escape;
+use super::{to_html, HtmlOptions, Sections};
+use crate::element::{walk_item_summary_mut, ItemKind, ItemSummary, TextComponent, VisitorMut};
+use crate::CrateIndex;
+
+/// How [`write_docset`] exports a crate.
+#[derive(Debug, Clone, Default)]
+pub struct DocsetOptions {
+    name: Option<String>,
+    sections: Sections,
+}
+
+impl DocsetOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Name shown in Dash and Zeal, the crate's name by default.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn sections(mut self, sections: Sections) -> Self {
+        self.sections = sections;
+        self
+    }
+}
+
+#[derive(Debug)]
+pub enum DocsetError {
+    Io(io::Error),
+    Index(rusqlite::Error),
+    /// The crawled pages are not under the crate's directory on docs.rs.
+    InvalidUrl(String),
+}
+
+impl fmt::Display for DocsetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DocsetError::Io(e) => write!(f, "cannot write docset: {}", e),
+            DocsetError::Index(e) => write!(f, "cannot write docset index: {}", e),
+            DocsetError::InvalidUrl(url) => write!(f, "unexpected page URL: {}", url),
+        }
+    }
+}
+
+impl Error for DocsetError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DocsetError::Io(e) => Some(e),
+            DocsetError::Index(e) => Some(e),
+            DocsetError::InvalidUrl(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for DocsetError {
+    fn from(e: io::Error) -> Self {
+        DocsetError::Io(e)
+    }
+}
+
+impl From<rusqlite::Error> for DocsetError {
+    fn from(e: rusqlite::Error) -> Self {
+        DocsetError::Index(e)
+    }
+}
+
+/// Dash's entry type for a kind of item.
+pub fn docset_entry_type(kind: ItemKind) -> &'static str {
+    match kind {
+        ItemKind::Module => "Module",
+        ItemKind::Struct => "Struct",
+        ItemKind::Enum => "Enum",
+        ItemKind::Constant => "Constant",
+        ItemKind::Function => "Function",
+        ItemKind::Trait => "Trait",
+        ItemKind::Macro => "Macro",
+        ItemKind::Attribute => "Attribute",
+        ItemKind::Type => "Type",
+        ItemKind::Primitive => "Builtin",
+        ItemKind::Keyword => "Keyword",
+    }
+}
+
+/// Writes a crawled crate as `<name>.docset` in `directory`, for Dash and Zeal.
+///
+/// Pages keep rustdoc's layout, so links between them work offline; links to
+/// pages outside the docset point at docs.rs instead. Returns the path of the docset.
+pub fn write_docset(index: &CrateIndex, directory: impl AsRef<Path>, options: &DocsetOptions) -> Result<PathBuf, DocsetError> {
+    let name = options.name.clone().unwrap_or_else(|| index.root().to_owned());
+    let docset = directory.as_ref().join(format!("{}.docset", name));
+    let contents = docset.join("Contents");
+    let documents = contents.join("Resources").join("Documents");
+    std::fs::create_dir_all(&documents)?;
+
+    let root = index
+        .get(index.root())
+        .ok_or_else(|| DocsetError::InvalidUrl(index.root().to_owned()))?;
+    let root_url = Url::parse(root.url()).map_err(|_| DocsetError::InvalidUrl(root.url().to_owned()))?;
+    // the directory with one subdirectory per crate, as relative links expect
+    let base = root_url.join("../").map_err(|_| DocsetError::InvalidUrl(root.url().to_owned()))?;
+    let page = |url: &str| {
+        Url::parse(url)
+            .ok()
+            .and_then(|url| base.make_relative(&url))
+            .filter(|page| !page.starts_with("../"))
+            .map(|page| page.split(['#', '?']).next().unwrap_or_default().to_owned())
+            .ok_or_else(|| DocsetError::InvalidUrl(url.to_owned()))
+    };
+
+    let database = contents.join("Resources").join("docSet.dsidx");
+    if database.exists() {
+        std::fs::remove_file(&database)?;
+    }
+    let mut connection = Connection::open(&database)?;
+    let transaction = connection.transaction()?;
+    transaction.execute_batch(
+        "CREATE TABLE searchIndex(id INTEGER PRIMARY KEY, name TEXT, type TEXT, path TEXT);
+         CREATE UNIQUE INDEX anchor ON searchIndex (name, type, path);",
+    )?;
+    {
+        let mut insert = transaction.prepare("INSERT OR IGNORE INTO searchIndex(name, type, path) VALUES (?1, ?2, ?3)")?;
+        let html_options = HtmlOptions::new().sections(options.sections);
+        let pages = index.iter().map(|(_, item)| page(item.url())).collect::<Result<HashSet<_>, _>>()?;
+        for (path, item) in index.iter() {
+            let page = page(item.url())?;
+            let file = documents.join(&page);
+            if let Some(parent) = file.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut document = item.document().clone();
+            let mut links = ExternalLinks {
+                page: Url::parse(item.url()).map_err(|_| DocsetError::InvalidUrl(item.url().to_owned()))?,
+                base: &base,
+                pages: &pages,
+            };
+            links.visit_document_mut(&mut document);
+            let html = format!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}</body>\n</html>\n",
+                escape(document.title()),
+                to_html(&document, &html_options)
+            );
+            std::fs::write(file, html)?;
+            insert.execute(params![path, docset_entry_type(item.kind()), page])?;
+        }
+        for (alias, path) in index.aliases() {
+            if let Some(item) = index.get(path) {
+                insert.execute(params![alias, docset_entry_type(item.kind()), page(item.url())?])?;
+            }
+        }
+    }
+    transaction.commit()?;
+
+    let plist = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>CFBundleIdentifier</key>
+	<string>{id}</string>
+	<key>CFBundleName</key>
+	<string>{name}</string>
+	<key>DocSetPlatformFamily</key>
+	<string>{id}</string>
+	<key>dashIndexFilePath</key>
+	<string>{index}</string>
+	<key>DashDocSetFallbackURL</key>
+	<string>{fallback}</string>
+	<key>isDashDocset</key>
+	<true/>
+	<key>isJavaScriptEnabled</key>
+	<false/>
+</dict>
+</plist>
+"#,
+        id = escape(index.root()),
+        name = escape(&name),
+        index = escape(&page(root.url())?),
+        fallback = escape(base.as_str()),
+    );
+    std::fs::write(contents.join("Info.plist"), plist)?;
+    Ok(docset)
+}
+
+/// Makes relative links to pages which are not in the docset absolute.
+struct ExternalLinks<'a> {
+    page: Url,
+    base: &'a Url,
+    pages: &'a HashSet<String>,
+}
+
+impl ExternalLinks<'_> {
+    fn rewrite(&self, link: &str) -> Option<String> {
+        if link.starts_with('#') || Url::parse(link).is_ok() {
+            return None;
+        }
+        let url = self.page.join(link).ok()?;
+        let page = self
+            .base
+            .make_relative(&url)
+            .filter(|page| !page.starts_with("../"))
+            .map(|page| page.split(['#', '?']).next().unwrap_or_default().to_owned());
+        match page {
+            Some(page) if self.pages.contains(&page) => None,
+            _ => Some(url.into()),
+        }
+    }
+}
+
+impl VisitorMut for ExternalLinks<'_> {
+    fn visit_item_summary_mut(&mut self, summary: &mut ItemSummary) {
+        if let Some(link) = summary.link().and_then(|link| self.rewrite(link)) {
+            summary.set_link(Some(link));
+        }
+        walk_item_summary_mut(self, summary);
+    }
+
+    fn visit_text_component_mut(&mut self, component: &mut TextComponent) {
+        if let Some(link) = component.link().and_then(|link| self.rewrite(link)) {
+            component.set_link(Some(link));
+        }
+    }
+}
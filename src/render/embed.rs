@@ -0,0 +1,484 @@
+use super::markdown::{code_fence, escape, inline_code, markdown_link, markdown_text};
+use super::{kind_heading, walk_sections, ImplSection, SectionWriter, Sections};
+use crate::element::*;
+
+/// Character limits of a chat platform's rich messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmbedLimits {
+    pub title: usize,
+    pub description: usize,
+    pub field_name: usize,
+    pub field_value: usize,
+    /// Number of fields.
+    pub fields: usize,
+    /// Characters in the title, description and fields together.
+    pub total: usize,
+}
+
+impl EmbedLimits {
+    /// Discord embeds.
+    pub fn discord() -> Self {
+        EmbedLimits {
+            title: 256,
+            description: 4096,
+            field_name: 256,
+            field_value: 1024,
+            fields: 25,
+            total: 6000,
+        }
+    }
+
+    /// Slack messages with a header block and a section block, its fields as section fields.
+    pub fn slack() -> Self {
+        EmbedLimits {
+            title: 150,
+            description: 3000,
+            field_name: 150,
+            field_value: 2000,
+            fields: 10,
+            total: 40000,
+        }
+    }
+}
+
+impl Default for EmbedLimits {
+    fn default() -> Self {
+        EmbedLimits::discord()
+    }
+}
+
+/// How [`to_embed`] formats a document.
+#[derive(Debug, Clone, Default)]
+pub struct EmbedOptions {
+    limits: EmbedLimits,
+    sections: Sections,
+    link_base: Option<String>,
+    url: Option<String>,
+}
+
+impl EmbedOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits to fit in, Discord's by default.
+    pub fn limits(mut self, limits: EmbedLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn sections(mut self, sections: Sections) -> Self {
+        self.sections = sections;
+        self
+    }
+
+    /// Resolves relative links against `base`, usually the directory of the page on docs.rs.
+    pub fn link_base(mut self, base: impl Into<String>) -> Self {
+        let mut base = base.into();
+        if !base.ends_with('/') {
+            base.push('/');
+        }
+        self.link_base = Some(base);
+        self
+    }
+
+    /// Links the title to the page, and the end of the description when anything was left out.
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+}
+
+/// A document cut down to a title, a description and named fields, all in Markdown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Embed {
+    title: String,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    url: Option<String>,
+    description: String,
+    fields: Vec<EmbedField>,
+    truncated: bool,
+}
+
+impl Embed {
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn fields(&self) -> &[EmbedField] {
+        &self.fields
+    }
+
+    /// Whether anything was shortened or left out to stay within the limits.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Characters counted against [`EmbedLimits::total`].
+    pub fn total_chars(&self) -> usize {
+        chars(&self.title)
+            + chars(&self.description)
+            + self
+                .fields
+                .iter()
+                .map(|field| chars(&field.name) + chars(&field.value))
+                .sum::<usize>()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EmbedField {
+    name: String,
+    value: String,
+}
+
+impl EmbedField {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+/// Formats a document as an embed within `options`' limits.
+///
+/// Text is cut at the end of a paragraph or sentence where possible, and code
+/// blocks at the end of a line, with their fence closed.
+pub fn to_embed(document: &Document, options: &EmbedOptions) -> Embed {
+    let limits = options.limits;
+    let writer = EmbedWriter { options };
+    let read_more = options
+        .url
+        .as_ref()
+        .map(|url| format!("[Read more]({})", markdown_link(None, url)));
+    // room to add the link to the description if it turns out to be needed
+    let reserve = read_more.as_ref().map_or(0, |link| chars(link) + 2);
+
+    let title = shorten(document.title(), limits.title);
+    let mut truncated = chars(&title) < chars(document.title());
+    let mut left = limits.total.saturating_sub(chars(&title) + reserve);
+
+    let (mut description, cut) = fit(&writer.intro(document), limits.description.saturating_sub(reserve).min(left));
+    truncated |= cut;
+    left -= chars(&description);
+
+    let mut fields = Vec::new();
+    for (name, blocks) in writer.fields(document) {
+        if fields.len() == limits.fields {
+            truncated = true;
+            break;
+        }
+        let name = shorten(&name, limits.field_name);
+        let (value, cut) = fit(&blocks, limits.field_value.min(left.saturating_sub(chars(&name))));
+        if value.is_empty() {
+            truncated = true;
+            break;
+        }
+        truncated |= cut;
+        left -= chars(&name) + chars(&value);
+        fields.push(EmbedField { name, value });
+    }
+
+    if let (true, Some(link)) = (truncated, read_more) {
+        if !description.is_empty() {
+            description.push_str("\n\n");
+        }
+        description.push_str(&link);
+    }
+    Embed {
+        title,
+        url: options.url.clone(),
+        description,
+        fields,
+        truncated,
+    }
+}
+
+/// A paragraph, list item or code block, and what joins it to the previous one.
+struct Block {
+    text: String,
+    separator: &'static str,
+    code: bool,
+}
+
+impl Block {
+    fn paragraph(text: String) -> Self {
+        Block {
+            text,
+            separator: "\n\n",
+            code: false,
+        }
+    }
+
+    fn line(text: String) -> Self {
+        Block {
+            text,
+            separator: "\n",
+            code: false,
+        }
+    }
+
+    fn code(code: &str, language: &str) -> Self {
+        Block {
+            text: code_fence(code, language),
+            separator: "\n\n",
+            code: true,
+        }
+    }
+}
+
+struct EmbedWriter<'a> {
+    options: &'a EmbedOptions,
+}
+
+impl EmbedWriter<'_> {
+    fn text(&self, text: &Text) -> String {
+        markdown_text(text, self.options.link_base.as_deref()).trim().to_owned()
+    }
+
+    fn mark(&self, mark: &Mark, blocks: &mut Vec<Block>) {
+        for note in [mark.stability(), mark.deprecated(), mark.features()] {
+            if !note.trim().is_empty() {
+                blocks.push(Block::paragraph(format!("> {}", escape(note.trim()))));
+            }
+        }
+    }
+
+    fn content(&self, content: &[Paragraph], blocks: &mut Vec<Block>) {
+        for paragraph in content {
+            match paragraph {
+                Paragraph::Text(text) => blocks.push(Block::paragraph(self.text(text))),
+                Paragraph::List(items) => {
+                    for (i, item) in items.iter().enumerate() {
+                        let item = format!("- {}", self.text(item));
+                        blocks.push(if i == 0 { Block::paragraph(item) } else { Block::line(item) });
+                    }
+                }
+                Paragraph::Code(code) => blocks.push(Block::code(code, "rust")),
+                Paragraph::InvalidCode(code) => blocks.push(Block::code(code, "")),
+                Paragraph::SubSection(section) => {
+                    if let Some(title) = section.title() {
+                        blocks.push(Block::paragraph(format!("**{}**", self.text(title))));
+                    }
+                    self.content(section.content(), blocks);
+                }
+            }
+        }
+    }
+
+    /// The mark, the declaration and the untitled part of the description.
+    fn intro(&self, document: &Document) -> Vec<Block> {
+        let mut blocks = Vec::new();
+        self.mark(document.mark(), &mut blocks);
+        if let Some(declaration) = document.declaration() {
+            blocks.push(Block::code(declaration, "rust"));
+        }
+        for section in self.options.sections.filter_description(document.description()) {
+            if section.title().is_none() {
+                self.content(section.content(), &mut blocks);
+            }
+        }
+        blocks
+    }
+
+    /// Titled sections of the description, then lists of items.
+    fn fields(&self, document: &Document) -> Vec<(String, Vec<Block>)> {
+        let sections = self.options.sections;
+        let mut fields = Vec::new();
+        for section in sections.filter_description(document.description()) {
+            if let Some(title) = section.title() {
+                let mut blocks = Vec::new();
+                self.content(section.content(), &mut blocks);
+                fields.push((title.to_string().trim().to_owned(), blocks));
+            }
+        }
+
+        let mut writer = FieldWriter { embed: self, fields };
+        walk_sections(&mut writer, document.kind(), sections);
+        let mut fields = writer.fields;
+        fields.retain(|(_, blocks)| !blocks.is_empty());
+        fields
+    }
+}
+
+fn code_lines<'a>(lines: impl Iterator<Item = &'a String>) -> Vec<Block> {
+    lines.map(|line| Block::line(inline_code(line))).collect()
+}
+
+/// Gathers the sections which depend on the kind of a document as fields.
+struct FieldWriter<'a> {
+    embed: &'a EmbedWriter<'a>,
+    fields: Vec<(String, Vec<Block>)>,
+}
+
+impl SectionWriter for FieldWriter<'_> {
+    fn module(&mut self, module: &Module) {
+        let exports = module.re_exports().iter().map(|export| export.declaration());
+        self.fields.push(("Re-exports".to_owned(), code_lines(exports)));
+        for summary in module.sub_item() {
+            let blocks = summary.content().iter().map(|item| {
+                let mut line = match item.link() {
+                    Some(href) => format!(
+                        "[{}]({})",
+                        inline_code(item.name()),
+                        markdown_link(self.embed.options.link_base.as_deref(), href)
+                    ),
+                    None => inline_code(item.name()),
+                };
+                let summary = self.embed.text(item.summary());
+                if !summary.is_empty() {
+                    line.push_str(": ");
+                    line.push_str(&summary);
+                }
+                Block::line(line)
+            });
+            self.fields.push((kind_heading(summary.item_type()).to_owned(), blocks.collect()));
+        }
+    }
+
+    fn simple_items(&mut self, title: &str, items: &[SimpleItem]) {
+        self.fields.push((title.to_owned(), code_lines(items.iter().map(SimpleItem::declaration))));
+    }
+
+    /// Only methods, trait impls and implementors are listed, as the rest rarely fit in a message.
+    fn implementations(&mut self, section: ImplSection, implementations: &[Implementation]) {
+        let lines = match section {
+            ImplSection::Methods => {
+                let methods = implementations.iter().flat_map(Implementation::methods);
+                code_lines(methods.map(SimpleItem::declaration))
+            }
+            ImplSection::Trait | ImplSection::Implementors => {
+                code_lines(implementations.iter().map(Implementation::impl_code))
+            }
+            ImplSection::Auto | ImplSection::Blanket | ImplSection::Foreign => return,
+        };
+        self.fields.push((section.title().to_owned(), lines));
+    }
+}
+
+fn chars(text: &str) -> usize {
+    text.chars().count()
+}
+
+/// Cuts a single line to `limit` characters, marking the cut with an ellipsis.
+fn shorten(text: &str, limit: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if chars(&text) <= limit {
+        return text;
+    }
+    let mut short: String = text.chars().take(limit.saturating_sub(1)).collect();
+    if limit > 0 {
+        short.push('…');
+    }
+    short
+}
+
+/// Joins as many blocks as fit in `limit` characters, cutting the first one
+/// that does not fit if a useful part of it does. Also returns whether
+/// anything was left out.
+fn fit(blocks: &[Block], limit: usize) -> (String, bool) {
+    let mut out = String::new();
+    for block in blocks {
+        let separator = if out.is_empty() { "" } else { block.separator };
+        let room = limit.saturating_sub(chars(&out) + chars(separator));
+        let cut = if chars(&block.text) <= room {
+            Some(block.text.clone())
+        } else if block.code {
+            cut_code(&block.text, room)
+        } else {
+            cut_text(&block.text, room, out.is_empty())
+        };
+        match cut {
+            Some(text) if text == block.text => {
+                out.push_str(separator);
+                out.push_str(&text);
+            }
+            Some(text) => {
+                out.push_str(separator);
+                out.push_str(&text);
+                return (out, true);
+            }
+            None => return (out, true),
+        }
+    }
+    (out, false)
+}
+
+/// Keeps whole lines of a fenced code block, closing the fence again.
+fn cut_code(block: &str, room: usize) -> Option<String> {
+    let mut lines: Vec<&str> = block.lines().collect();
+    let close = lines.pop()?;
+    let mut out = lines.remove(0).to_owned();
+    let mut kept = 0;
+    for line in lines {
+        if chars(&out) + 1 + chars(line) + 1 + chars(close) > room {
+            break;
+        }
+        out.push('\n');
+        out.push_str(line);
+        kept += 1;
+    }
+    if kept == 0 {
+        return None;
+    }
+    out.push('\n');
+    out.push_str(close);
+    Some(out)
+}
+
+/// Keeps whole sentences, or whole words with an ellipsis when nothing else has been kept.
+fn cut_text(text: &str, room: usize, alone: bool) -> Option<String> {
+    let (sentences, words) = boundaries(text);
+    let fits = |end: &&usize| chars(text[..**end].trim_end()) <= room;
+    if let Some(&end) = sentences.iter().rev().find(fits) {
+        return Some(text[..end].trim_end().to_owned());
+    }
+    if !alone {
+        return None;
+    }
+    let ellipsis = |end: &&usize| chars(text[..**end].trim_end()) < room;
+    let end = *words.iter().rev().find(ellipsis)?;
+    Some(format!("{}…", text[..end].trim_end()))
+}
+
+/// Byte offsets where Markdown text can be cut without breaking code spans
+/// or links: after the end of each sentence, and at each space.
+fn boundaries(text: &str) -> (Vec<usize>, Vec<usize>) {
+    let mut sentences = Vec::new();
+    let mut words = Vec::new();
+    let mut code = false;
+    let mut depth = 0usize;
+    let mut escaped = false;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        let outside = !code && depth == 0;
+        match c {
+            '\\' => escaped = true,
+            '`' => code = !code,
+            '[' | '(' if !code => depth += 1,
+            ']' | ')' if !code => depth = depth.saturating_sub(1),
+            '.' | '!' | '?' if outside && chars.peek().is_none_or(|(_, next)| next.is_whitespace()) => {
+                sentences.push(i + 1)
+            }
+            c if c.is_whitespace() && outside => words.push(i),
+            _ => {}
+        }
+    }
+    (sentences, words)
+}
@@ -0,0 +1,256 @@
+use super::{kind_heading, resolve_link, walk_sections, ImplSection, SectionWriter, Sections};
+use crate::element::*;
+
+/// Schemes links may use, besides relative links and fragments.
+const SAFE_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// How [`to_html`] renders a document.
+#[derive(Debug, Clone, Default)]
+pub struct HtmlOptions {
+    heading_offset: usize,
+    sections: Sections,
+    link_base: Option<String>,
+}
+
+impl HtmlOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves every heading down by `offset` levels, to embed the document in a
+    /// page with headings of its own. Headings never go deeper than `<h6>`.
+    pub fn heading_offset(mut self, offset: usize) -> Self {
+        self.heading_offset = offset;
+        self
+    }
+
+    pub fn sections(mut self, sections: Sections) -> Self {
+        self.sections = sections;
+        self
+    }
+
+    /// Resolves relative links against `base`, usually the directory of the page on docs.rs.
+    pub fn link_base(mut self, base: impl Into<String>) -> Self {
+        let mut base = base.into();
+        if !base.ends_with('/') {
+            base.push('/');
+        }
+        self.link_base = Some(base);
+        self
+    }
+}
+
+/// Renders a document as an HTML fragment, without styles or scripts.
+///
+/// Only the tags needed for the document's structure are emitted, the only
+/// attributes are `href` and the `class` of code blocks, and links with any
+/// scheme but http, https and mailto are left out.
+pub fn to_html(document: &Document, options: &HtmlOptions) -> String {
+    let mut writer = HtmlWriter {
+        options,
+        out: String::new(),
+    };
+    writer.document(document);
+    writer.out
+}
+
+struct HtmlWriter<'a> {
+    options: &'a HtmlOptions,
+    out: String,
+}
+
+impl HtmlWriter<'_> {
+    fn heading(&mut self, level: usize, title: &str) {
+        let level = (level + self.options.heading_offset).clamp(1, 6);
+        self.out.push_str(&format!("<h{}>{}</h{}>\n", level, title, level));
+    }
+
+    fn code_block(&mut self, code: &str, language: Option<&str>) {
+        self.out.push_str("<pre>");
+        match language {
+            Some(language) => self.out.push_str(&format!("<code class=\"language-{}\">", language)),
+            None => self.out.push_str("<code>"),
+        }
+        self.out.push_str(&escape(code.trim_end_matches('\n')));
+        self.out.push_str("</code></pre>\n");
+    }
+
+    /// Resolves `href` against the link base, or gives `None` for links that are not safe to follow.
+    fn link(&self, href: &str) -> Option<String> {
+        // browsers ignore these inside a URL, so `java\tscript:` is still a scheme
+        let href: String = href.trim().chars().filter(|c| !c.is_ascii_control()).collect();
+        let scheme = href.split_once(':').map(|(scheme, _)| scheme).filter(|scheme| {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+        });
+        match (scheme, &self.options.link_base) {
+            (Some(scheme), _) => SAFE_SCHEMES.contains(&scheme.to_ascii_lowercase().as_str()).then_some(href),
+            (None, Some(base)) if !href.starts_with('#') && !href.starts_with("//") => Some(resolve_link(base, &href)),
+            (None, _) => Some(href),
+        }
+    }
+
+    fn anchor(&self, content: &str, href: &str) -> String {
+        match self.link(href) {
+            Some(url) => format!("<a href=\"{}\">{}</a>", escape(&url), content),
+            None => content.to_owned(),
+        }
+    }
+
+    fn text(&self, text: &Text) -> String {
+        let mut out = String::new();
+        for component in text.components() {
+            let mut content = escape(component.text());
+            if component.is_code() {
+                content = format!("<code>{}</code>", content);
+            }
+            if component.is_italic() {
+                content = format!("<em>{}</em>", content);
+            }
+            if component.is_bold() {
+                content = format!("<strong>{}</strong>", content);
+            }
+            if let Some(href) = component.link() {
+                content = self.anchor(&content, href);
+            }
+            out.push_str(&content);
+        }
+        out
+    }
+
+    fn mark(&mut self, mark: &Mark) {
+        for note in [mark.stability(), mark.deprecated(), mark.features()] {
+            if !note.trim().is_empty() {
+                self.out.push_str(&format!("<blockquote>{}</blockquote>\n", escape(note.trim())));
+            }
+        }
+    }
+
+    fn section(&mut self, section: &Section, level: usize) {
+        if let Some(title) = section.title() {
+            let title = self.text(title);
+            self.heading(level, title.trim());
+        }
+        for paragraph in section.content() {
+            match paragraph {
+                Paragraph::Text(text) => {
+                    let text = self.text(text);
+                    self.out.push_str(&format!("<p>{}</p>\n", text.trim()));
+                }
+                Paragraph::List(items) => {
+                    self.out.push_str("<ul>\n");
+                    for item in items {
+                        let item = self.text(item);
+                        self.out.push_str(&format!("<li>{}</li>\n", item.trim()));
+                    }
+                    self.out.push_str("</ul>\n");
+                }
+                Paragraph::Code(code) => self.code_block(code, Some("rust")),
+                Paragraph::InvalidCode(code) => self.code_block(code, None),
+                Paragraph::SubSection(section) => self.section(section, level + 1),
+            }
+        }
+    }
+
+    fn description(&mut self, description: &[Section], level: usize) {
+        for section in self.options.sections.filter_description(description) {
+            self.section(section, level);
+        }
+    }
+
+    fn document(&mut self, document: &Document) {
+        let sections = self.options.sections;
+        self.heading(1, &escape(document.title()));
+        self.mark(document.mark());
+        if let Some(declaration) = document.declaration() {
+            self.code_block(declaration, Some("rust"));
+        }
+        self.description(document.description(), 2);
+        walk_sections(self, document.kind(), sections);
+    }
+
+    /// Fields, variants and methods each get a heading with their declaration.
+    fn simple_item(&mut self, item: &SimpleItem, level: usize) {
+        self.heading(level, &code(item.declaration()));
+        self.mark(item.mark());
+        self.description(item.description(), level + 1);
+    }
+}
+
+impl SectionWriter for HtmlWriter<'_> {
+    fn module(&mut self, module: &Module) {
+        if !module.re_exports().is_empty() {
+            self.heading(2, "Re-exports");
+            self.out.push_str("<ul>\n");
+            for export in module.re_exports() {
+                self.out.push_str(&format!("<li>{}</li>\n", code(export.declaration())));
+            }
+            self.out.push_str("</ul>\n");
+        }
+        for summary in module.sub_item() {
+            self.heading(2, kind_heading(summary.item_type()));
+            self.out.push_str("<ul>\n");
+            for item in summary.content() {
+                let name = code(item.name());
+                let mut line = match item.link() {
+                    Some(href) => self.anchor(&name, href),
+                    None => name,
+                };
+                let summary = self.text(item.summary());
+                if !summary.trim().is_empty() {
+                    line.push_str(": ");
+                    line.push_str(summary.trim());
+                }
+                self.out.push_str(&format!("<li>{}</li>\n", line));
+            }
+            self.out.push_str("</ul>\n");
+        }
+    }
+
+    fn simple_items(&mut self, title: &str, items: &[SimpleItem]) {
+        self.heading(2, title);
+        for item in items {
+            self.simple_item(item, 3);
+        }
+    }
+
+    fn implementations(&mut self, section: ImplSection, implementations: &[Implementation]) {
+        self.heading(2, section.title());
+        if section.lists_items() {
+            for implementation in implementations {
+                self.heading(3, &code(implementation.impl_code()));
+                for item in implementation.associated_types().iter().chain(implementation.methods()) {
+                    self.simple_item(item, 4);
+                }
+            }
+        } else {
+            self.out.push_str("<ul>\n");
+            for implementation in implementations {
+                self.out.push_str(&format!("<li>{}</li>\n", code(implementation.impl_code())));
+            }
+            self.out.push_str("</ul>\n");
+        }
+    }
+}
+
+/// Inline code on one line.
+fn code(code: &str) -> String {
+    let code = code.split_whitespace().collect::<Vec<_>>().join(" ");
+    format!("<code>{}</code>", escape(&code))
+}
+
+/// Escapes text for both element content and quoted attribute values.
+pub(super) fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
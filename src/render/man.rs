@@ -0,0 +1,328 @@
+use super::{kind_heading, walk_sections, ImplSection, SectionWriter, Sections};
+use crate::element::*;
+#[cfg(feature = "client")]
+use crate::CrateIndex;
+#[cfg(feature = "client")]
+use std::io;
+#[cfg(feature = "client")]
+use std::path::{Path, PathBuf};
+
+/// How [`to_man`] renders a document.
+#[derive(Debug, Clone)]
+pub struct ManOptions {
+    section: String,
+    sections: Sections,
+}
+
+impl Default for ManOptions {
+    fn default() -> Self {
+        ManOptions {
+            section: "3".to_owned(),
+            sections: Sections::default(),
+        }
+    }
+}
+
+impl ManOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Manual section of the pages and of their references to each other, `3` by default.
+    pub fn section(mut self, section: impl Into<String>) -> Self {
+        self.section = section.into();
+        self
+    }
+
+    pub fn sections(mut self, sections: Sections) -> Self {
+        self.sections = sections;
+        self
+    }
+}
+
+/// Renders a document as a roff man page, named after the item's path.
+pub fn to_man(document: &Document, options: &ManOptions) -> String {
+    let mut writer = ManWriter {
+        options,
+        path: page_name(document),
+        out: String::new(),
+        implementations: false,
+    };
+    writer.document(document);
+    writer.out
+}
+
+/// Writes a man page for every item of a crawled crate to the `man<section>`
+/// directory in `directory`, and for every re-export a page that includes the
+/// original. Returns the files written.
+#[cfg(feature = "client")]
+pub fn write_man_pages(index: &CrateIndex, directory: impl AsRef<Path>, options: &ManOptions) -> io::Result<Vec<PathBuf>> {
+    let subdirectory = format!("man{}", options.section.trim_end_matches(|c: char| !c.is_ascii_digit()));
+    let man_directory = directory.as_ref().join(&subdirectory);
+    std::fs::create_dir_all(&man_directory)?;
+    let file_name = |path: &str| format!("{}.{}", path, options.section);
+
+    let mut written = Vec::new();
+    for (path, item) in index.iter() {
+        let file = man_directory.join(file_name(path));
+        std::fs::write(&file, to_man(item.document(), options))?;
+        written.push(file);
+    }
+    for (alias, path) in index.aliases() {
+        if index.get(path).is_some() {
+            let file = man_directory.join(file_name(alias));
+            std::fs::write(&file, format!(".so {}/{}\n", subdirectory, file_name(path)))?;
+            written.push(file);
+        }
+    }
+    Ok(written)
+}
+
+/// The path in a title such as "Struct tokio::net::TcpStream".
+fn page_name(document: &Document) -> String {
+    document.title().split_whitespace().last().unwrap_or_default().to_owned()
+}
+
+struct ManWriter<'a> {
+    options: &'a ManOptions,
+    path: String,
+    out: String,
+    /// Whether the heading shared by all lists of trait impls has been written.
+    implementations: bool,
+}
+
+impl ManWriter<'_> {
+    /// Adds a line of text, which must not be taken for a request.
+    fn line(&mut self, line: &str) {
+        if line.starts_with('.') || line.starts_with('\'') {
+            self.out.push_str("\\&");
+        }
+        self.out.push_str(line);
+        self.out.push('\n');
+    }
+
+    fn request(&mut self, request: &str) {
+        self.out.push_str(request);
+        self.out.push('\n');
+    }
+
+    fn heading(&mut self, title: &str) {
+        self.request(&format!(".SH {}", quote(&escape(&title.to_uppercase()))));
+    }
+
+    fn code(&mut self, code: &str) {
+        self.request(".nf");
+        for line in code.trim_end_matches('\n').lines() {
+            self.line(&escape(line));
+        }
+        self.request(".fi");
+    }
+
+    fn text(&self, text: &Text) -> String {
+        let mut out = String::new();
+        for component in text.components() {
+            let content = escape(component.text());
+            let font = if component.is_italic() {
+                "\\fI"
+            } else if component.is_bold() || component.is_code() {
+                "\\fB"
+            } else {
+                ""
+            };
+            if font.is_empty() {
+                out.push_str(&content);
+            } else {
+                out.push_str(&format!("{}{}\\fR", font, content));
+            }
+        }
+        out.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    fn mark(&mut self, mark: &Mark, paragraph: &str) {
+        for note in [mark.stability(), mark.deprecated(), mark.features()] {
+            if !note.trim().is_empty() {
+                self.request(paragraph);
+                let note = escape(note).split_whitespace().collect::<Vec<_>>().join(" ");
+                self.line(&format!("\\fI{}\\fR", note));
+            }
+        }
+    }
+
+    /// Renders the content of a section, starting paragraphs with `.PP` at the
+    /// top level and with `.IP` inside an item, so they stay indented.
+    fn content(&mut self, content: &[Paragraph], paragraph: &str) {
+        for item in content {
+            match item {
+                Paragraph::Text(text) => {
+                    self.request(paragraph);
+                    let text = self.text(text);
+                    self.line(&text);
+                }
+                Paragraph::List(items) => {
+                    for item in items {
+                        let item = self.text(item);
+                        self.request(".IP \\(bu 2");
+                        self.line(&item);
+                    }
+                }
+                Paragraph::Code(code) | Paragraph::InvalidCode(code) => {
+                    self.request(paragraph);
+                    self.code(code);
+                }
+                Paragraph::SubSection(section) => self.titled(section, paragraph, false),
+            }
+        }
+    }
+
+    fn titled(&mut self, section: &Section, paragraph: &str, top: bool) {
+        if let Some(title) = section.title() {
+            let title = self.text(title);
+            if top {
+                self.request(&format!(".SS {}", quote(&title)));
+            } else {
+                self.request(paragraph);
+                self.line(&format!("\\fB{}\\fR", title));
+            }
+        }
+        self.content(section.content(), paragraph);
+    }
+
+    fn description(&mut self, description: &[Section], paragraph: &str) {
+        let top = paragraph == ".PP";
+        for section in self.options.sections.filter_description(description) {
+            self.titled(section, paragraph, top);
+        }
+    }
+
+    /// The first sentence of the description, for the NAME section.
+    fn summary(&self, document: &Document) -> String {
+        let text = document
+            .description()
+            .first()
+            .filter(|section| section.title().is_none())
+            .and_then(|section| section.content().first())
+            .and_then(|paragraph| match paragraph {
+                Paragraph::Text(text) => Some(text.to_string()),
+                _ => None,
+            })
+            .unwrap_or_default();
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let sentence = text.split(". ").next().unwrap_or_default();
+        escape(sentence.trim_end_matches('.'))
+    }
+
+    fn document(&mut self, document: &Document) {
+        let sections = self.options.sections;
+        let crate_name = self.path.split("::").next().unwrap_or_default().to_owned();
+        self.request(&format!(
+            ".TH {} {} \"\" {} \"Rust Documentation\"",
+            quote(&self.path),
+            quote(&self.options.section),
+            quote(&crate_name)
+        ));
+
+        self.heading("Name");
+        let summary = self.summary(document);
+        if summary.is_empty() {
+            self.line(&escape(&self.path.clone()));
+        } else {
+            self.line(&format!("{} \\- {}", escape(&self.path), summary));
+        }
+
+        if let Some(declaration) = document.declaration() {
+            self.heading("Synopsis");
+            self.code(declaration);
+        }
+
+        let mark = document.mark();
+        let marked = [mark.stability(), mark.deprecated(), mark.features()]
+            .iter()
+            .any(|note| !note.trim().is_empty());
+        if marked || !document.description().is_empty() {
+            self.heading("Description");
+            self.mark(mark, ".PP");
+            self.description(document.description(), ".PP");
+        }
+
+        walk_sections(self, document.kind(), sections);
+    }
+
+    fn simple_item(&mut self, item: &SimpleItem) {
+        self.request(".TP");
+        let declaration = escape(item.declaration()).split_whitespace().collect::<Vec<_>>().join(" ");
+        self.line(&format!("\\fB{}\\fR", declaration));
+        self.mark(item.mark(), ".IP");
+        self.description(item.description(), ".IP");
+    }
+}
+
+impl SectionWriter for ManWriter<'_> {
+    /// Lists items by the names of their own pages, as `write_man_pages` writes them.
+    fn module(&mut self, module: &Module) {
+        if !module.re_exports().is_empty() {
+            self.heading("Re-exports");
+            for export in module.re_exports() {
+                self.request(".PP");
+                self.code(export.declaration());
+            }
+        }
+        for summary in module.sub_item() {
+            self.heading(kind_heading(summary.item_type()));
+            for item in summary.content() {
+                self.request(".TP");
+                let name = escape(item.name());
+                let tag = if item.link().is_some() {
+                    format!("\\fB{}::{}\\fR({})", escape(&self.path), name, escape(&self.options.section))
+                } else {
+                    format!("\\fB{}\\fR", name)
+                };
+                self.line(&tag);
+                let summary = self.text(item.summary());
+                if !summary.is_empty() {
+                    self.line(&summary);
+                }
+            }
+        }
+    }
+
+    fn simple_items(&mut self, title: &str, items: &[SimpleItem]) {
+        self.heading(title);
+        for item in items {
+            self.simple_item(item);
+        }
+    }
+
+    fn implementations(&mut self, section: ImplSection, implementations: &[Implementation]) {
+        if section.lists_items() {
+            self.heading(section.title());
+            for implementation in implementations {
+                let impl_code = escape(implementation.impl_code());
+                self.request(&format!(".SS {}", quote(&impl_code)));
+                for item in implementation.associated_types().iter().chain(implementation.methods()) {
+                    self.simple_item(item);
+                }
+            }
+        } else {
+            if !self.implementations {
+                self.heading("Implementations");
+                self.implementations = true;
+            }
+            self.request(&format!(".SS {}", quote(section.title())));
+            for implementation in implementations {
+                self.request(".IP \\(bu 2");
+                let impl_code = escape(implementation.impl_code());
+                self.line(&format!("\\fB{}\\fR", impl_code.split_whitespace().collect::<Vec<_>>().join(" ")));
+            }
+        }
+    }
+}
+
+/// Escapes text for roff, including dashes so code can be copied from the page.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\e").replace('-', "\\-")
+}
+
+/// Quotes an argument of a request.
+fn quote(argument: &str) -> String {
+    format!("\"{}\"", argument.replace('"', "\\(dq"))
+}
@@ -0,0 +1,303 @@
+use super::{kind_heading, resolve_link, walk_sections, ImplSection, SectionWriter, Sections};
+use crate::element::*;
+
+/// How [`to_markdown`] renders a document.
+#[derive(Debug, Clone, Default)]
+pub struct MarkdownOptions {
+    heading_offset: usize,
+    sections: Sections,
+    link_base: Option<String>,
+}
+
+impl MarkdownOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves every heading down by `offset` levels, to embed the document under
+    /// headings of its own. Headings never go deeper than level 6.
+    pub fn heading_offset(mut self, offset: usize) -> Self {
+        self.heading_offset = offset;
+        self
+    }
+
+    pub fn sections(mut self, sections: Sections) -> Self {
+        self.sections = sections;
+        self
+    }
+
+    /// Resolves relative links against `base`, usually the directory of the page on docs.rs.
+    pub fn link_base(mut self, base: impl Into<String>) -> Self {
+        let mut base = base.into();
+        if !base.ends_with('/') {
+            base.push('/');
+        }
+        self.link_base = Some(base);
+        self
+    }
+}
+
+/// Renders a document as CommonMark.
+pub fn to_markdown(document: &Document, options: &MarkdownOptions) -> String {
+    let mut writer = MarkdownWriter {
+        options,
+        out: String::new(),
+    };
+    writer.document(document);
+    writer.out
+}
+
+struct MarkdownWriter<'a> {
+    options: &'a MarkdownOptions,
+    out: String,
+}
+
+impl MarkdownWriter<'_> {
+    /// Starts a new block, separated from the previous one by a blank line.
+    fn block(&mut self) {
+        if !self.out.is_empty() {
+            while !self.out.ends_with("\n\n") {
+                self.out.push('\n');
+            }
+        }
+    }
+
+    fn heading(&mut self, level: usize, title: &str) {
+        self.block();
+        let level = (level + self.options.heading_offset).clamp(1, 6);
+        self.out.push_str(&"#".repeat(level));
+        self.out.push(' ');
+        self.out.push_str(title);
+        self.out.push('\n');
+    }
+
+    fn code_block(&mut self, code: &str, language: &str) {
+        self.block();
+        self.out.push_str(&code_fence(code, language));
+        self.out.push('\n');
+    }
+
+    fn link(&self, href: &str) -> String {
+        markdown_link(self.options.link_base.as_deref(), href)
+    }
+
+    fn text(&self, text: &Text) -> String {
+        markdown_text(text, self.options.link_base.as_deref())
+    }
+
+    fn mark(&mut self, mark: &Mark) {
+        for note in [mark.stability(), mark.deprecated(), mark.features()] {
+            if !note.trim().is_empty() {
+                self.block();
+                self.out.push_str("> ");
+                self.out.push_str(&escape(note.trim()));
+                self.out.push('\n');
+            }
+        }
+    }
+
+    fn section(&mut self, section: &Section, level: usize) {
+        if let Some(title) = section.title() {
+            let title = self.text(title);
+            self.heading(level, &title);
+        }
+        for paragraph in section.content() {
+            match paragraph {
+                Paragraph::Text(text) => {
+                    self.block();
+                    let text = self.text(text);
+                    self.out.push_str(text.trim());
+                    self.out.push('\n');
+                }
+                Paragraph::List(items) => {
+                    self.block();
+                    for item in items {
+                        let item = self.text(item);
+                        self.out.push_str("- ");
+                        self.out.push_str(item.trim());
+                        self.out.push('\n');
+                    }
+                }
+                Paragraph::Code(code) => self.code_block(code, "rust"),
+                Paragraph::InvalidCode(code) => self.code_block(code, ""),
+                Paragraph::SubSection(section) => self.section(section, level + 1),
+            }
+        }
+    }
+
+    fn description(&mut self, description: &[Section], level: usize) {
+        for section in self.options.sections.filter_description(description) {
+            self.section(section, level);
+        }
+    }
+
+    fn document(&mut self, document: &Document) {
+        let sections = self.options.sections;
+        self.heading(1, &escape(document.title()));
+        self.mark(document.mark());
+        if let Some(declaration) = document.declaration() {
+            self.code_block(declaration, "rust");
+        }
+        self.description(document.description(), 2);
+        walk_sections(self, document.kind(), sections);
+    }
+
+    /// Fields, variants and methods each get a heading with their declaration.
+    fn simple_item(&mut self, item: &SimpleItem, level: usize) {
+        self.heading(level, &inline_code(item.declaration()));
+        self.mark(item.mark());
+        self.description(item.description(), level + 1);
+    }
+}
+
+impl SectionWriter for MarkdownWriter<'_> {
+    fn module(&mut self, module: &Module) {
+        if !module.re_exports().is_empty() {
+            self.heading(2, "Re-exports");
+            self.block();
+            for export in module.re_exports() {
+                self.out.push_str("- ");
+                self.out.push_str(&inline_code(export.declaration()));
+                self.out.push('\n');
+            }
+        }
+        for summary in module.sub_item() {
+            self.heading(2, kind_heading(summary.item_type()));
+            self.block();
+            for item in summary.content() {
+                let name = match item.link() {
+                    Some(href) => format!("[{}]({})", inline_code(item.name()), self.link(href)),
+                    None => inline_code(item.name()),
+                };
+                let summary = self.text(item.summary());
+                self.out.push_str("- ");
+                self.out.push_str(&name);
+                if !summary.trim().is_empty() {
+                    self.out.push_str(": ");
+                    self.out.push_str(summary.trim());
+                }
+                self.out.push('\n');
+            }
+        }
+    }
+
+    fn simple_items(&mut self, title: &str, items: &[SimpleItem]) {
+        self.heading(2, title);
+        for item in items {
+            self.simple_item(item, 3);
+        }
+    }
+
+    fn implementations(&mut self, section: ImplSection, implementations: &[Implementation]) {
+        self.heading(2, section.title());
+        if section.lists_items() {
+            for implementation in implementations {
+                self.heading(3, &inline_code(implementation.impl_code()));
+                for item in implementation.associated_types().iter().chain(implementation.methods()) {
+                    self.simple_item(item, 4);
+                }
+            }
+        } else {
+            self.block();
+            for implementation in implementations {
+                self.out.push_str("- ");
+                self.out.push_str(&inline_code(implementation.impl_code()));
+                self.out.push('\n');
+            }
+        }
+    }
+}
+
+/// Makes `href` absolute with `base`, as set by [`MarkdownOptions::link_base`].
+pub(super) fn markdown_link(base: Option<&str>, href: &str) -> String {
+    let url = match base {
+        Some(base) if !href.contains("://") && !href.starts_with('#') => resolve_link(base, href),
+        _ => href.to_owned(),
+    };
+    if url.contains(|c: char| c.is_whitespace() || c == '(' || c == ')') {
+        format!("<{}>", url)
+    } else {
+        url
+    }
+}
+
+pub(super) fn markdown_text(text: &Text, link_base: Option<&str>) -> String {
+    let mut out = String::new();
+    for component in text.components() {
+        let mut content = if component.is_code() {
+            inline_code(component.text())
+        } else {
+            escape(component.text())
+        };
+        if component.is_italic() {
+            content = emphasize(&content, "*");
+        }
+        if component.is_bold() {
+            content = emphasize(&content, "**");
+        }
+        if let Some(href) = component.link() {
+            content = format!("[{}]({})", content, markdown_link(link_base, href));
+        }
+        out.push_str(&content);
+    }
+    out
+}
+
+/// Fences code with more backticks than any run inside it.
+pub(super) fn code_fence(code: &str, language: &str) -> String {
+    let fence = "`".repeat(longest_run(code, '`').max(2) + 1);
+    format!("{}{}\n{}\n{}", fence, language, code.trim_end_matches('\n'), fence)
+}
+
+fn longest_run(text: &str, c: char) -> usize {
+    let mut longest = 0;
+    let mut run = 0;
+    for ch in text.chars() {
+        run = if ch == c { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    longest
+}
+
+/// Wraps code in enough backticks that those inside it do not end the span.
+pub(super) fn inline_code(code: &str) -> String {
+    let code = code.split_whitespace().collect::<Vec<_>>().join(" ");
+    let ticks = "`".repeat(longest_run(&code, '`') + 1);
+    if code.starts_with('`') || code.ends_with('`') {
+        format!("{} {} {}", ticks, code, ticks)
+    } else {
+        format!("{}{}{}", ticks, code, ticks)
+    }
+}
+
+/// Puts emphasis markers inside surrounding whitespace, where CommonMark recognizes them.
+fn emphasize(text: &str, marker: &str) -> String {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return text.to_owned();
+    }
+    let start = text.len() - text.trim_start().len();
+    let end = text.trim_end().len();
+    format!("{}{}{}{}{}", &text[..start], marker, trimmed, marker, &text[end..])
+}
+
+pub(super) fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut previous = ' ';
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let intraword = previous.is_alphanumeric() && chars.peek().is_some_and(|next| next.is_alphanumeric());
+        previous = c;
+        match c {
+            '\n' => out.push(' '),
+            // snake_case names cannot start emphasis, so they are left readable
+            '_' if intraword => out.push(c),
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|' | '~' => {
+                out.push('\\');
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,236 @@
+//! Renders documents into other formats.
+
+#[cfg(feature = "docset")]
+mod docset;
+mod embed;
+mod html;
+mod man;
+mod markdown;
+mod plain;
+#[cfg(feature = "client")]
+mod symbols;
+
+#[cfg(feature = "docset")]
+pub use docset::*;
+pub use embed::*;
+pub use html::*;
+pub use man::*;
+pub use markdown::*;
+pub use plain::*;
+#[cfg(feature = "client")]
+pub use symbols::*;
+
+use crate::element::{
+    walk_section, DocumentKind, Implementable, Implementation, ItemKind, Module, Section, SimpleItem, Visitor,
+};
+
+/// Parts of a document to render.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sections {
+    pub description: bool,
+    /// Sections titled "Examples", which are also part of the description.
+    pub examples: bool,
+    pub methods: bool,
+    pub fields: bool,
+    pub variants: bool,
+    pub impls: bool,
+    pub items: bool,
+}
+
+impl Sections {
+    /// Everything, with examples as part of the description.
+    pub fn all() -> Self {
+        Sections {
+            description: true,
+            examples: false,
+            methods: true,
+            fields: true,
+            variants: true,
+            impls: true,
+            items: true,
+        }
+    }
+
+    pub fn none() -> Self {
+        Sections {
+            description: false,
+            examples: false,
+            methods: false,
+            fields: false,
+            variants: false,
+            impls: false,
+            items: false,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Sections::none()
+    }
+
+    /// Picks the sections of `description` to render: all of them, only the examples, or none.
+    pub fn filter_description<'a>(&self, description: &'a [Section]) -> Vec<&'a Section> {
+        if self.description {
+            description.iter().collect()
+        } else if self.examples {
+            let mut examples = Examples(Vec::new());
+            description.iter().for_each(|section| examples.visit_section(section));
+            examples.0
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl Default for Sections {
+    fn default() -> Self {
+        Sections::all()
+    }
+}
+
+/// Collects the outermost sections titled "Examples".
+struct Examples<'a>(Vec<&'a Section>);
+
+impl<'a> Visitor<'a> for Examples<'a> {
+    fn visit_section(&mut self, section: &'a Section) {
+        let is_example = section
+            .title()
+            .is_some_and(|title| title.to_string().trim().to_lowercase().starts_with("example"));
+        if is_example {
+            self.0.push(section);
+        } else {
+            walk_section(self, section);
+        }
+    }
+}
+
+/// Heading of a list of items of one kind, as rustdoc titles it.
+pub fn kind_heading(kind: ItemKind) -> &'static str {
+    match kind {
+        ItemKind::Module => "Modules",
+        ItemKind::Struct => "Structs",
+        ItemKind::Enum => "Enums",
+        ItemKind::Constant => "Constants",
+        ItemKind::Function => "Functions",
+        ItemKind::Trait => "Traits",
+        ItemKind::Macro => "Macros",
+        ItemKind::Attribute => "Attribute Macros",
+        ItemKind::Type => "Type Definitions",
+        ItemKind::Primitive => "Primitive Types",
+        ItemKind::Keyword => "Keywords",
+    }
+}
+
+/// Resolves a relative `href` against `base` as a browser would, so `/`-rooted and
+/// `../` links work. A `base` that is not an absolute URL is simply prefixed.
+fn resolve_link(base: &str, href: &str) -> String {
+    match url::Url::parse(base).and_then(|base| base.join(href)) {
+        Ok(url) => url.into(),
+        Err(_) => format!("{}{}", base, href),
+    }
+}
+
+/// A list of implementations on a page, in the order rustdoc shows them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImplSection {
+    /// Inherent impls, whose methods are listed in full.
+    Methods,
+    Trait,
+    Auto,
+    Blanket,
+    /// Impls of a trait for types outside its crate.
+    Foreign,
+    Implementors,
+}
+
+impl ImplSection {
+    /// Heading of the list, as rustdoc titles it.
+    pub fn title(self) -> &'static str {
+        match self {
+            ImplSection::Methods => "Methods",
+            ImplSection::Trait => "Trait Implementations",
+            ImplSection::Auto => "Auto Trait Implementations",
+            ImplSection::Blanket => "Blanket Implementations",
+            ImplSection::Foreign => "Implementations on Foreign Types",
+            ImplSection::Implementors => "Implementors",
+        }
+    }
+
+    /// Whether the items of each implementation are shown, rather than only its header.
+    pub fn lists_items(self) -> bool {
+        self == ImplSection::Methods
+    }
+}
+
+/// Formats the sections of a document which depend on its kind, as [`walk_sections`] picks them.
+pub trait SectionWriter {
+    fn module(&mut self, module: &Module);
+
+    /// Fields, variants, associated types or required methods, never empty.
+    fn simple_items(&mut self, title: &str, items: &[SimpleItem]);
+
+    /// Never called with an empty list.
+    fn implementations(&mut self, section: ImplSection, implementations: &[Implementation]);
+}
+
+/// Writes the sections of `kind` which `sections` selects, in the order rustdoc shows them.
+pub fn walk_sections(writer: &mut impl SectionWriter, kind: &DocumentKind, sections: Sections) {
+    match kind {
+        DocumentKind::Crate(module) | DocumentKind::Module(module) if sections.items => writer.module(module),
+        DocumentKind::Struct(item) => {
+            if sections.fields {
+                simple_items(writer, "Fields", item.fields());
+            }
+            if sections.methods {
+                implementations(writer, ImplSection::Methods, item.methods());
+            }
+            if sections.impls {
+                implementable(writer, item);
+            }
+        }
+        DocumentKind::Enum(item) => {
+            if sections.variants {
+                simple_items(writer, "Variants", item.variants());
+            }
+            if sections.impls {
+                implementable(writer, item);
+            }
+        }
+        DocumentKind::Trait(item) => {
+            if sections.methods {
+                simple_items(writer, "Associated Types", item.associated_types());
+                simple_items(writer, "Required Methods", item.required_methods());
+            }
+            if sections.impls {
+                implementations(writer, ImplSection::Foreign, item.foreign_implementations());
+                implementations(writer, ImplSection::Implementors, item.implementors());
+            }
+        }
+        DocumentKind::Primitive(item) => {
+            if sections.methods {
+                implementations(writer, ImplSection::Methods, item.methods());
+            }
+            if sections.impls {
+                implementable(writer, item);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn simple_items(writer: &mut impl SectionWriter, title: &str, items: &[SimpleItem]) {
+    if !items.is_empty() {
+        writer.simple_items(title, items);
+    }
+}
+
+fn implementations(writer: &mut impl SectionWriter, section: ImplSection, implementations: &[Implementation]) {
+    if !implementations.is_empty() {
+        writer.implementations(section, implementations);
+    }
+}
+
+fn implementable(writer: &mut impl SectionWriter, item: &impl Implementable) {
+    implementations(writer, ImplSection::Trait, item.trait_impls());
+    implementations(writer, ImplSection::Auto, item.auto_impls());
+    implementations(writer, ImplSection::Blanket, item.blanket_impls());
+}
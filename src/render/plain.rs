@@ -0,0 +1,291 @@
+use unicode_width::UnicodeWidthStr;
+use super::{kind_heading, resolve_link, walk_sections, ImplSection, SectionWriter, Sections};
+use crate::element::*;
+
+const DEFAULT_WIDTH: usize = 80;
+
+/// How [`to_plain_text`] renders a document.
+#[derive(Debug, Clone)]
+pub struct PlainTextOptions {
+    width: usize,
+    sections: Sections,
+    link_base: Option<String>,
+}
+
+impl Default for PlainTextOptions {
+    fn default() -> Self {
+        PlainTextOptions {
+            width: DEFAULT_WIDTH,
+            sections: Sections::default(),
+            link_base: None,
+        }
+    }
+}
+
+impl PlainTextOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wraps lines at `width` columns, 80 by default.
+    ///
+    /// Code and words longer than a line are never broken.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn sections(mut self, sections: Sections) -> Self {
+        self.sections = sections;
+        self
+    }
+
+    /// Resolves relative links against `base`, usually the directory of the page on docs.rs.
+    pub fn link_base(mut self, base: impl Into<String>) -> Self {
+        let mut base = base.into();
+        if !base.ends_with('/') {
+            base.push('/');
+        }
+        self.link_base = Some(base);
+        self
+    }
+}
+
+/// Renders a document as wrapped plain text, followed by the links it contains.
+pub fn to_plain_text(document: &Document, options: &PlainTextOptions) -> String {
+    let mut writer = PlainTextWriter::new(options);
+    writer.document(document);
+    writer.finish()
+}
+
+/// Renders one section like [`to_plain_text`].
+pub fn section_to_plain_text(section: &Section, options: &PlainTextOptions) -> String {
+    let mut writer = PlainTextWriter::new(options);
+    writer.section(section, 0, 0);
+    writer.finish()
+}
+
+/// Renders a paragraph of text like [`to_plain_text`].
+pub fn text_to_plain_text(text: &Text, options: &PlainTextOptions) -> String {
+    let mut writer = PlainTextWriter::new(options);
+    let text = writer.text(text);
+    writer.wrap(&text, 0, 0);
+    writer.finish()
+}
+
+struct PlainTextWriter<'a> {
+    options: &'a PlainTextOptions,
+    out: String,
+    links: Vec<String>,
+}
+
+impl<'a> PlainTextWriter<'a> {
+    fn new(options: &'a PlainTextOptions) -> Self {
+        PlainTextWriter {
+            options,
+            out: String::new(),
+            links: Vec::new(),
+        }
+    }
+
+    fn finish(mut self) -> String {
+        if !self.links.is_empty() {
+            self.block();
+            let links = std::mem::take(&mut self.links);
+            for (number, link) in links.iter().enumerate() {
+                self.out.push_str(&format!("[{}] {}\n", number + 1, link));
+            }
+        }
+        self.out
+    }
+
+    /// Starts a new block, separated from the previous one by a blank line.
+    fn block(&mut self) {
+        if !self.out.is_empty() {
+            while !self.out.ends_with("\n\n") {
+                self.out.push('\n');
+            }
+        }
+    }
+
+    fn line(&mut self, indent: usize, line: &str) {
+        self.out.extend(std::iter::repeat_n(' ', indent));
+        self.out.push_str(line);
+        self.out.push('\n');
+    }
+
+    /// Fills lines up to the width, starting the first at `indent` and the rest at `hanging`.
+    fn wrap(&mut self, text: &str, indent: usize, hanging: usize) {
+        let mut line = String::new();
+        let mut start = indent;
+        for word in text.split_whitespace() {
+            if !line.is_empty() && start + line.width() + 1 + word.width() > self.options.width {
+                self.line(start, &line);
+                line.clear();
+                start = hanging;
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        if !line.is_empty() {
+            self.line(start, &line);
+        }
+    }
+
+    fn code(&mut self, code: &str, indent: usize) {
+        self.block();
+        for line in code.trim_end_matches('\n').lines() {
+            if line.is_empty() {
+                self.out.push('\n');
+            } else {
+                self.line(indent, line);
+            }
+        }
+    }
+
+    fn heading(&mut self, title: &str, underline: char, indent: usize) {
+        self.block();
+        let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+        self.line(indent, &title);
+        self.line(indent, &underline.to_string().repeat(title.width()));
+    }
+
+    /// Numbers a link, reusing the number of an earlier link to the same place.
+    fn reference(&mut self, href: &str) -> usize {
+        let url = match &self.options.link_base {
+            Some(base) if !href.contains("://") && !href.starts_with('#') => resolve_link(base, href),
+            _ => href.to_owned(),
+        };
+        match self.links.iter().position(|link| *link == url) {
+            Some(index) => index + 1,
+            None => {
+                self.links.push(url);
+                self.links.len()
+            }
+        }
+    }
+
+    fn text(&mut self, text: &Text) -> String {
+        let mut out = String::new();
+        let components = text.components();
+        for (i, component) in components.iter().enumerate() {
+            out.push_str(component.text());
+            if let Some(href) = component.link() {
+                // one reference after all the pieces of a link
+                if components.get(i + 1).and_then(TextComponent::link) != Some(href) {
+                    let number = self.reference(href);
+                    out.push_str(&format!("[{}]", number));
+                }
+            }
+        }
+        out
+    }
+
+    fn mark(&mut self, mark: &Mark, indent: usize) {
+        for note in [mark.stability(), mark.deprecated(), mark.features()] {
+            if !note.trim().is_empty() {
+                self.block();
+                self.wrap(note, indent, indent);
+            }
+        }
+    }
+
+    fn section(&mut self, section: &Section, indent: usize, depth: usize) {
+        if let Some(title) = section.title() {
+            let underline = if depth == 0 { '-' } else { '~' };
+            self.heading(&title.to_string(), underline, indent);
+        }
+        let inner = if depth > 0 { indent + 2 } else { indent };
+        for paragraph in section.content() {
+            match paragraph {
+                Paragraph::Text(text) => {
+                    self.block();
+                    let text = self.text(text);
+                    self.wrap(&text, inner, inner);
+                }
+                Paragraph::List(items) => {
+                    self.block();
+                    for item in items {
+                        let item = format!("- {}", self.text(item));
+                        self.wrap(&item, inner + 2, inner + 4);
+                    }
+                }
+                Paragraph::Code(code) | Paragraph::InvalidCode(code) => self.code(code, inner + 4),
+                Paragraph::SubSection(section) => self.section(section, inner + 2, depth + 1),
+            }
+        }
+    }
+
+    fn description(&mut self, description: &[Section], indent: usize) {
+        for section in self.options.sections.filter_description(description) {
+            self.section(section, indent, 0);
+        }
+    }
+
+    fn document(&mut self, document: &Document) {
+        let sections = self.options.sections;
+        self.heading(document.title(), '=', 0);
+        self.mark(document.mark(), 0);
+        if let Some(declaration) = document.declaration() {
+            self.code(declaration, 4);
+        }
+        self.description(document.description(), 0);
+        walk_sections(self, document.kind(), sections);
+    }
+
+    fn simple_item(&mut self, item: &SimpleItem, indent: usize) {
+        self.code(item.declaration(), indent);
+        self.mark(item.mark(), indent + 4);
+        self.description(item.description(), indent + 4);
+    }
+}
+
+impl SectionWriter for PlainTextWriter<'_> {
+    fn module(&mut self, module: &Module) {
+        if !module.re_exports().is_empty() {
+            self.heading("Re-exports", '-', 0);
+            self.block();
+            for export in module.re_exports() {
+                self.line(4, export.declaration());
+            }
+        }
+        for summary in module.sub_item() {
+            self.heading(kind_heading(summary.item_type()), '-', 0);
+            self.block();
+            for item in summary.content() {
+                let mut line = item.name().to_owned();
+                if let Some(href) = item.link() {
+                    let number = self.reference(href);
+                    line.push_str(&format!("[{}]", number));
+                }
+                let summary = self.text(item.summary());
+                if !summary.trim().is_empty() {
+                    line.push_str(": ");
+                    line.push_str(&summary);
+                }
+                self.wrap(&line, 4, 8);
+            }
+        }
+    }
+
+    fn simple_items(&mut self, title: &str, items: &[SimpleItem]) {
+        self.heading(title, '-', 0);
+        for item in items {
+            self.simple_item(item, 4);
+        }
+    }
+
+    fn implementations(&mut self, section: ImplSection, implementations: &[Implementation]) {
+        self.heading(section.title(), '-', 0);
+        for implementation in implementations {
+            self.code(implementation.impl_code(), 4);
+            if section.lists_items() {
+                for item in implementation.associated_types().iter().chain(implementation.methods()) {
+                    self.simple_item(item, 8);
+                }
+            }
+        }
+    }
+}
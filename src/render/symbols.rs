@@ -0,0 +1,178 @@
+use reqwest::Url;
+use crate::element::{Document, DocumentKind, ItemContainer, ItemKind, Marked};
+use crate::CrateIndex;
+
+/// An item of a crawled crate, for editors to jump to its documentation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Symbol {
+    path: String,
+    kind: ItemKind,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    declaration: Option<String>,
+    deprecated: bool,
+    summary: String,
+    url: String,
+}
+
+impl Symbol {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Last segment of the path.
+    pub fn name(&self) -> &str {
+        self.path.rsplit("::").next().unwrap_or(&self.path)
+    }
+
+    pub fn kind(&self) -> ItemKind {
+        self.kind
+    }
+
+    /// Declaration of the item, if its page was crawled and has one.
+    pub fn declaration(&self) -> Option<&str> {
+        self.declaration.as_deref()
+    }
+
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated
+    }
+
+    pub fn summary(&self) -> &str {
+        &self.summary
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+/// Lists every item of a crawled crate, sorted by path.
+///
+/// Items come from the summaries of each module, so those whose pages could
+/// not be fetched or parsed are still listed, only without a declaration.
+pub fn symbols(index: &CrateIndex) -> Vec<Symbol> {
+    let mut symbols = Vec::new();
+    if let Some(root) = index.get(index.root()) {
+        symbols.push(Symbol {
+            path: index.root().to_owned(),
+            kind: root.kind(),
+            declaration: None,
+            deprecated: is_deprecated(root.document()),
+            summary: String::new(),
+            url: root.url().to_owned(),
+        });
+    }
+    for (path, item) in index.iter() {
+        let module = match item.document().kind() {
+            DocumentKind::Crate(module) | DocumentKind::Module(module) => module,
+            _ => continue,
+        };
+        let page = match Url::parse(item.url()) {
+            Ok(page) => page,
+            Err(_) => continue,
+        };
+        for section in module.sub_item() {
+            for summary in section.content() {
+                let url = match summary.link().and_then(|link| page.join(link).ok()) {
+                    Some(url) => url,
+                    None => continue,
+                };
+                let item_path = format!("{}::{}", path, summary.name());
+                let document = index.document(&item_path);
+                symbols.push(Symbol {
+                    kind: section.item_type(),
+                    declaration: document.and_then(Document::declaration).map(|code| code.to_string()),
+                    deprecated: !summary.attribute().deprecated().trim().is_empty() || document.is_some_and(is_deprecated),
+                    summary: summary.summary().to_string().split_whitespace().collect::<Vec<_>>().join(" "),
+                    url: url.into(),
+                    path: item_path,
+                });
+            }
+        }
+    }
+    symbols.sort_by(|a, b| a.path.cmp(&b.path));
+    symbols.dedup_by(|a, b| a.path == b.path);
+    symbols
+}
+
+fn is_deprecated(document: &Document) -> bool {
+    !document.mark().deprecated().trim().is_empty()
+}
+
+/// Formats symbols as an extended ctags file, tagging each name with the URL of its page.
+///
+/// Vim and Emacs open the URL when jumping to a tag; the full path, kind and
+/// declaration are in the `path`, `kind` and `signature` fields.
+pub fn to_ctags(symbols: &[Symbol]) -> String {
+    let mut tags: Vec<String> = symbols
+        .iter()
+        .map(|symbol| {
+            let mut line = format!(
+                "{}\t{}\t1;\"\tkind:{}\tpath:{}",
+                symbol.name(),
+                symbol.url(),
+                tag_kind(symbol.kind()),
+                field(symbol.path())
+            );
+            if let Some(declaration) = symbol.declaration() {
+                line.push_str("\tsignature:");
+                line.push_str(&field(declaration));
+            }
+            if symbol.is_deprecated() {
+                line.push_str("\tdeprecated:yes");
+            }
+            line
+        })
+        .collect();
+    // sorted by name, which comes first on each line
+    tags.sort();
+
+    let mut out = String::from(
+        "!_TAG_FILE_FORMAT\t2\t/extended format/\n\
+         !_TAG_FILE_SORTED\t1\t/0=unsorted, 1=sorted, 2=foldcase/\n\
+         !_TAG_PROGRAM_NAME\tparadocs\t//\n",
+    );
+    for tag in tags {
+        out.push_str(&tag);
+        out.push('\n');
+    }
+    out
+}
+
+/// Formats symbols as a JSON object from each path to the rest of its symbol.
+#[cfg(feature = "serde")]
+pub fn to_symbol_json(symbols: &[Symbol]) -> String {
+    let table: serde_json::Map<String, serde_json::Value> = symbols
+        .iter()
+        .map(|symbol| {
+            let mut value = serde_json::to_value(symbol).expect("symbols serialize to JSON");
+            if let Some(object) = value.as_object_mut() {
+                object.remove("path");
+            }
+            (symbol.path.clone(), value)
+        })
+        .collect();
+    serde_json::to_string_pretty(&table).expect("symbols serialize to JSON")
+}
+
+/// The kind names universal-ctags uses for Rust.
+fn tag_kind(kind: ItemKind) -> &'static str {
+    match kind {
+        ItemKind::Module => "module",
+        ItemKind::Struct => "struct",
+        ItemKind::Enum => "enum",
+        ItemKind::Constant => "constant",
+        ItemKind::Function => "function",
+        ItemKind::Trait => "interface",
+        ItemKind::Macro | ItemKind::Attribute => "macro",
+        ItemKind::Type => "typedef",
+        ItemKind::Primitive => "primitive",
+        ItemKind::Keyword => "keyword",
+    }
+}
+
+/// A value of an extension field, on one line and without tabs.
+fn field(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ").replace('\\', "\\\\")
+}
@@ -0,0 +1,49 @@
+use paradocs::*;
+
+fn client() -> blocking::DocsClient {
+    blocking::DocsClient::builder()
+        .replay(concat!(env!("CARGO_MANIFEST_DIR"), "/test/fixtures"))
+        .build_blocking()
+        .unwrap()
+}
+
+#[test]
+fn test_get_document() {
+    let document = client().get_document("paradocs_fixture::hello").unwrap();
+    assert_eq!(document.title(), "Function paradocs_fixture::hello");
+}
+
+#[test]
+fn test_crawl() {
+    let index = client().crawl("paradocs_fixture", &CrawlOptions::new()).unwrap();
+    assert_eq!(index.len(), 3);
+}
+
+#[test]
+fn test_crawl_iter() {
+    let client = client();
+    let paths: Vec<ItemPath> = client
+        .crawl_iter("paradocs_fixture", CrawlOptions::new())
+        .filter_map(|item| item.ok())
+        .map(|(path, _)| path)
+        .collect();
+    assert_eq!(paths.len(), 3);
+    assert_eq!(paths[0], "paradocs_fixture");
+}
+
+#[tokio::main]
+async fn get_document_in_runtime() {
+    client().get_document("paradocs_fixture::hello");
+}
+
+#[test]
+#[should_panic(expected = "cannot be used inside a tokio runtime")]
+fn test_inside_runtime() {
+    get_document_in_runtime();
+}
+
+#[tokio::test]
+#[should_panic(expected = "cannot be used inside a tokio runtime")]
+async fn test_inside_basic_runtime() {
+    client().crawl_iter("paradocs_fixture", CrawlOptions::new()).next();
+}
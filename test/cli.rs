@@ -0,0 +1,106 @@
+use std::process::{Command, Output};
+
+fn paradocs(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_paradocs"))
+        .args(args)
+        .env("PARADOCS_REPLAY", concat!(env!("CARGO_MANIFEST_DIR"), "/test/fixtures"))
+        .env_remove("PARADOCS_RECORD")
+        .output()
+        .unwrap()
+}
+
+fn stdout(args: &[&str]) -> String {
+    let output = paradocs(args);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_plain() {
+    let out = stdout(&["paradocs_fixture::hello"]);
+    assert!(out.starts_with("Function paradocs_fixture::hello\n\npub fn hello()\n"));
+    assert!(out.contains("Says hello."));
+    assert!(out.contains("# Examples\n\n    paradocs_fixture::hello();\n"));
+    assert!(!out.contains('\x1b'));
+
+    let out = stdout(&["paradocs-fixture"]);
+    assert!(out.contains("Re-exports\n    pub use sub::Thing;\n"));
+    assert!(out.contains("Modules\n    sub  Nested items.\n"));
+}
+
+#[test]
+fn test_color() {
+    let out = stdout(&["--color", "always", "paradocs-fixture"]);
+    assert!(out.contains("\x1b[1mCrate paradocs_fixture\x1b[0m"));
+    assert!(out.contains(
+        "\x1b]8;;https://docs.rs/paradocs-fixture/0.1.0/paradocs_fixture/sub/index.html\x1b\\\x1b[36msub\x1b[0m\x1b]8;;\x1b\\"
+    ));
+}
+
+#[test]
+fn test_control_characters() {
+    let out = stdout(&["paradocs_fixture::evil"]);
+    assert!(out.starts_with("Function paradocs_fixture::evil]0;pwned\n\npub fn evil[2J()\n"));
+    assert!(out.contains("Clears Hthe screen\tnow."));
+    assert!(!out.contains(|c: char| c.is_control() && c != '\n' && c != '\t'));
+
+    let out = stdout(&["--color", "always", "paradocs_fixture::evil"]);
+    assert!(!out.contains("\x1b]0;") && !out.contains("\x1b[2J"));
+    assert!(!out.contains(['\x07', '\u{9b}']));
+}
+
+#[test]
+fn test_sections() {
+    let out = stdout(&["--examples", "paradocs_fixture::hello"]);
+    assert!(out.contains("# Examples"));
+    assert!(!out.contains("Says hello."));
+
+    let out = stdout(&["--items", "paradocs-fixture"]);
+    assert!(out.contains("Functions\n    hello  Says hello.\n"));
+    assert!(!out.contains("A crate for testing"));
+}
+
+#[test]
+fn test_json() {
+    let out = stdout(&["--json", "paradocs_fixture::hello"]);
+    let value: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(value["kind"]["kind"], "function");
+    assert!(!paradocs(&["--json", "--methods", "paradocs_fixture::hello"]).status.success());
+}
+
+#[test]
+fn test_not_found() {
+    let output = paradocs(&["not_recorded"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no documentation found for `not_recorded`"));
+}
+
+#[test]
+fn test_man() {
+    let out = stdout(&["--man", "paradocs_fixture::hello"]);
+    assert!(out.starts_with(".TH \"paradocs_fixture::hello\" \"3\""));
+
+    let directory = std::env::temp_dir().join("paradocs-cli-man");
+    let _ = std::fs::remove_dir_all(&directory);
+    let out = stdout(&["--man-dir", directory.to_str().unwrap(), "paradocs_fixture"]);
+    assert_eq!(out.lines().count(), 4);
+    let man3 = directory.join("man3");
+    let page = std::fs::read_to_string(man3.join("paradocs_fixture::hello.3")).unwrap();
+    assert!(page.contains("paradocs_fixture::hello \\- Says hello\n"));
+    assert_eq!(
+        std::fs::read_to_string(man3.join("paradocs_fixture::sub::hello.3")).unwrap(),
+        ".so man3/paradocs_fixture::hello.3\n"
+    );
+}
+
+#[test]
+fn test_symbols() {
+    let out = stdout(&["--symbols", "paradocs_fixture"]);
+    let table: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(table["paradocs_fixture::hello"]["declaration"], "pub fn hello()");
+    assert_eq!(table["paradocs_fixture::sub::Thing"]["kind"], "struct");
+    assert_eq!(table["paradocs_fixture::sub::Thing"]["deprecated"], false);
+
+    let out = stdout(&["--tags", "paradocs_fixture"]);
+    assert!(out.contains("\nsub\thttps://docs.rs/paradocs-fixture/0.1.0/paradocs_fixture/sub/index.html\t1;\"\tkind:module"));
+}
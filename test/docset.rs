@@ -0,0 +1,54 @@
+use paradocs::blocking::DocsClient;
+use paradocs::render::*;
+use paradocs::CrawlOptions;
+use rusqlite::Connection;
+
+#[test]
+fn test_docset() {
+    let client = DocsClient::builder()
+        .replay(concat!(env!("CARGO_MANIFEST_DIR"), "/test/fixtures"))
+        .build_blocking()
+        .unwrap();
+    let index = client.crawl("paradocs_fixture", &CrawlOptions::new()).unwrap();
+    let directory = std::env::temp_dir().join("paradocs-docset");
+    let _ = std::fs::remove_dir_all(&directory);
+    let docset = write_docset(&index, &directory, &DocsetOptions::new()).unwrap();
+    assert_eq!(docset, directory.join("paradocs_fixture.docset"));
+
+    let contents = docset.join("Contents");
+    let plist = std::fs::read_to_string(contents.join("Info.plist")).unwrap();
+    assert!(plist.contains("<key>dashIndexFilePath</key>\n\t<string>paradocs_fixture/index.html</string>"));
+    assert!(plist.contains("<key>DashDocSetFallbackURL</key>\n\t<string>https://docs.rs/paradocs-fixture/"));
+
+    let connection = Connection::open(contents.join("Resources/docSet.dsidx")).unwrap();
+    let mut query = connection.prepare("SELECT name, type, path FROM searchIndex ORDER BY name").unwrap();
+    let entries: Vec<(String, String, String)> = query
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let entries: Vec<(&str, &str, &str)> = entries
+        .iter()
+        .map(|(name, kind, path)| (name.as_str(), kind.as_str(), path.as_str()))
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            ("paradocs_fixture", "Module", "paradocs_fixture/index.html"),
+            ("paradocs_fixture::hello", "Function", "paradocs_fixture/fn.hello.html"),
+            ("paradocs_fixture::sub", "Module", "paradocs_fixture/sub/index.html"),
+            ("paradocs_fixture::sub::hello", "Function", "paradocs_fixture/fn.hello.html"),
+        ]
+    );
+
+    let documents = contents.join("Resources/Documents/paradocs_fixture");
+    let page = std::fs::read_to_string(documents.join("index.html")).unwrap();
+    assert!(page.contains("<title>Crate paradocs_fixture</title>"));
+    assert!(page.contains("<a href=\"sub/index.html\"><code>sub</code></a>"));
+    let page = std::fs::read_to_string(documents.join("sub/index.html")).unwrap();
+    assert!(page.contains("<a href=\"../fn.hello.html\"><code>hello</code></a>"));
+    // the struct is not part of the docset, so its link leads to docs.rs
+    assert!(page.contains(
+        "<a href=\"https://docs.rs/paradocs-fixture/0.1.0/paradocs_fixture/sub/struct.Thing.html\"><code>Thing</code></a>"
+    ));
+}
@@ -0,0 +1,170 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use serde_json::{json, Value};
+
+/// A language server talking over pipes, killed when dropped.
+struct Server {
+    child: Child,
+    input: ChildStdin,
+    output: BufReader<ChildStdout>,
+    next_id: u64,
+}
+
+impl Server {
+    fn start() -> Self {
+        Self::replaying(Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/test/fixtures")))
+    }
+
+    fn replaying(fixtures: &Path) -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_paradocs-lsp"))
+            .env("PARADOCS_REPLAY", fixtures)
+            .env_remove("PARADOCS_RECORD")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let input = child.stdin.take().unwrap();
+        let output = BufReader::new(child.stdout.take().unwrap());
+        let mut server = Server {
+            child,
+            input,
+            output,
+            next_id: 0,
+        };
+        let result = server.request("initialize", json!({ "capabilities": {} }));
+        assert_eq!(result["capabilities"]["hoverProvider"], true);
+        server.notify("initialized", json!({}));
+        server
+    }
+
+    fn send(&mut self, message: Value) {
+        let body = message.to_string();
+        write!(self.input, "Content-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+        self.input.flush().unwrap();
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }));
+
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            self.output.read_line(&mut line).unwrap();
+            match line.trim_end().split_once(": ") {
+                Some(("Content-Length", value)) => length = value.parse().unwrap(),
+                _ if line.trim_end().is_empty() => break,
+                _ => {}
+            }
+        }
+        let mut body = vec![0; length];
+        self.output.read_exact(&mut body).unwrap();
+        let response: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(response["id"], id);
+        response.get("result").cloned().unwrap_or_else(|| response["error"].clone())
+    }
+
+    fn open(&mut self, uri: &str, text: &str) {
+        self.notify(
+            "textDocument/didOpen",
+            json!({ "textDocument": { "uri": uri, "languageId": "markdown", "version": 1, "text": text } }),
+        );
+    }
+
+    fn at(&mut self, method: &str, uri: &str, line: u32, character: u32) -> Value {
+        self.request(method, json!({ "textDocument": { "uri": uri }, "position": { "line": line, "character": character } }))
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn test_hover() {
+    let mut server = Server::start();
+    server.open("file:///notes.md", "# Notes\n\nCall `paradocs_fixture::hello()` first.\n");
+    let hover = server.at("textDocument/hover", "file:///notes.md", 2, 25);
+    assert_eq!(
+        hover["contents"]["value"],
+        "**Function paradocs_fixture::hello**\n\n```rust\npub fn hello()\n```\n\nSays *hello*."
+    );
+    assert_eq!(hover["range"], json!({ "start": { "line": 2, "character": 6 }, "end": { "line": 2, "character": 29 } }));
+
+    // the segment under the cursor decides how much of the path is looked up
+    let hover = server.at("textDocument/hover", "file:///notes.md", 2, 8);
+    assert_eq!(hover["range"]["end"]["character"], 22);
+    let value = hover["contents"]["value"].as_str().unwrap();
+    assert!(value.starts_with("**Crate paradocs_fixture**"));
+    assert!(value.contains("[`sub`](https://docs.rs/paradocs-fixture/0.1.0/paradocs_fixture/sub/index.html)"));
+
+    assert_eq!(server.at("textDocument/hover", "file:///notes.md", 0, 3), Value::Null);
+}
+
+#[test]
+fn test_completion() {
+    let mut server = Server::start();
+    server.open("file:///Cargo.toml", "[package.metadata]\nentry = \"paradocs_fixture::\"\nthing = \"paradocs_fixture::sub::Th\"\n");
+    let completion = server.at("textDocument/completion", "file:///Cargo.toml", 1, 27);
+    let labels: Vec<(&str, u64)> = completion["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| (item["label"].as_str().unwrap(), item["kind"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(labels, [("hello", 3), ("sub", 9)]);
+
+    let completion = server.at("textDocument/completion", "file:///Cargo.toml", 2, 34);
+    let item = &completion["items"][0];
+    assert_eq!(item["detail"], "paradocs_fixture::sub::Thing");
+    assert_eq!(
+        item["textEdit"],
+        json!({
+            "range": { "start": { "line": 2, "character": 32 }, "end": { "line": 2, "character": 34 } },
+            "newText": "Thing",
+        })
+    );
+
+    assert_eq!(server.request("shutdown", Value::Null), Value::Null);
+    server.notify("exit", Value::Null);
+    assert!(server.child.wait().unwrap().success());
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &to.join(entry.file_name()));
+        } else {
+            fs::copy(entry.path(), to.join(entry.file_name())).unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_completion_failure() {
+    let fixtures = std::env::temp_dir().join("paradocs-lsp-fixtures");
+    let _ = fs::remove_dir_all(&fixtures);
+    fs::create_dir_all(&fixtures).unwrap();
+    let mut server = Server::replaying(&fixtures);
+    server.open("file:///notes.md", "paradocs_fixture::\n");
+    let completion = server.at("textDocument/completion", "file:///notes.md", 0, 18);
+    assert_eq!(completion["items"], json!([]));
+
+    // the failed listing is remembered instead of being fetched again on every keystroke
+    copy_dir(Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/test/fixtures")), &fixtures);
+    let completion = server.at("textDocument/completion", "file:///notes.md", 0, 18);
+    assert_eq!(completion["items"], json!([]));
+    let _ = fs::remove_dir_all(&fixtures);
+}
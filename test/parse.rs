@@ -0,0 +1,198 @@
+use paradocs::element::*;
+
+/// Reads the page body out of a recorded response.
+fn fixture_page(path: &str) -> String {
+    let fixture = std::fs::read_to_string(format!(
+        "{}/test/fixtures/docs.rs/paradocs-fixture/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        path
+    ))
+    .unwrap();
+    fixture.split_once("\n\n").unwrap().1.to_owned()
+}
+
+#[test]
+fn test_parse_crate_root() {
+    let html = fixture_page("%2A/paradocs_fixture/index.html.get");
+    let document = Document::from_html(&html).unwrap();
+    assert_eq!(document.title(), "Crate paradocs_fixture");
+    let text = document.description()[0].content();
+    match &text[0] {
+        Paragraph::Text(text) => {
+            let bold: Vec<&str> = text.components().iter().filter(|c| c.is_bold()).map(|c| c.text()).collect();
+            assert_eq!(bold, ["paradocs"]);
+            let link = text.components().iter().find(|c| c.is_code()).unwrap();
+            assert_eq!(link.text(), "sub");
+            assert_eq!(link.link(), Some("sub/index.html"));
+        }
+        paragraph => panic!("expected text, found {:?}", paragraph),
+    }
+}
+
+#[test]
+fn test_parse_function() {
+    let html = fixture_page("0.1.0/paradocs_fixture/fn.hello.html.get");
+    let document = Document::from_html(&html).unwrap();
+    match document.kind() {
+        DocumentKind::Function(declaration) => assert_eq!(declaration, "pub fn hello()"),
+        kind => panic!("expected a function, found {:?}", kind),
+    }
+    let examples = document.description()[1].title().unwrap();
+    assert_eq!(examples.components()[0].text(), "Examples");
+}
+
+#[test]
+fn test_parse_invalid() {
+    assert!(Document::from_html("<html><body><p>not rustdoc</p></body></html>").is_none());
+}
+
+/// Collects declarations, code blocks and links from every part of a document.
+#[derive(Default)]
+struct Collector<'a> {
+    declarations: Vec<&'a str>,
+    code: Vec<&'a str>,
+    links: Vec<&'a str>,
+    summaries: Vec<String>,
+}
+
+impl<'a> Visitor<'a> for Collector<'a> {
+    fn visit_declaration(&mut self, code: &'a Code) {
+        self.declarations.push(code);
+    }
+
+    fn visit_code(&mut self, code: &'a Code) {
+        self.code.push(code);
+    }
+
+    fn visit_text_component(&mut self, component: &'a TextComponent) {
+        self.links.extend(component.link());
+    }
+
+    fn visit_item_summary(&mut self, summary: &'a ItemSummary) {
+        self.summaries.push(summary.summary().to_string());
+        walk_item_summary(self, summary);
+    }
+}
+
+#[test]
+fn test_visitor() {
+    let html = fixture_page("%2A/paradocs_fixture/index.html.get");
+    let document = Document::from_html(&html).unwrap();
+    let mut collector = Collector::default();
+    collector.visit_document(&document);
+    assert_eq!(collector.declarations, ["pub use sub::Thing;"]);
+    assert_eq!(collector.code, ["paradocs_fixture::hello();"]);
+    assert_eq!(collector.links, ["sub/index.html"]);
+    assert_eq!(collector.summaries, ["Nested items.", "Says hello."]);
+
+    let html = fixture_page("0.1.0/paradocs_fixture/fn.hello.html.get");
+    let document = Document::from_html(&html).unwrap();
+    let mut collector = Collector::default();
+    collector.visit_document(&document);
+    assert_eq!(collector.declarations, ["pub fn hello()"]);
+}
+
+/// Points relative links at docs.rs.
+struct AbsoluteLinks;
+
+impl VisitorMut for AbsoluteLinks {
+    fn visit_text_component_mut(&mut self, component: &mut TextComponent) {
+        if let Some(link) = component.link().filter(|link| !link.contains("://")) {
+            let link = format!("https://docs.rs/paradocs-fixture/latest/paradocs_fixture/{}", link);
+            component.set_link(Some(link));
+        }
+    }
+}
+
+#[test]
+fn test_visitor_mut() {
+    let html = fixture_page("%2A/paradocs_fixture/index.html.get");
+    let mut document = Document::from_html(&html).unwrap();
+    AbsoluteLinks.visit_document_mut(&mut document);
+    let mut collector = Collector::default();
+    collector.visit_document(&document);
+    assert_eq!(collector.links, ["https://docs.rs/paradocs-fixture/latest/paradocs_fixture/sub/index.html"]);
+}
+
+/// Clears stability notes and marks everything as deprecated.
+struct Deprecate;
+
+impl VisitorMut for Deprecate {
+    fn visit_mark_mut(&mut self, mark: &mut Mark) {
+        mark.set_stability("");
+        mark.set_deprecated("Deprecated since 0.2");
+    }
+}
+
+#[test]
+fn test_visitor_mut_marks() {
+    let html = fixture_page("%2A/paradocs_fixture/index.html.get");
+    let mut document = Document::from_html(&html).unwrap();
+    Deprecate.visit_document_mut(&mut document);
+    assert_eq!(document.mark().deprecated(), "Deprecated since 0.2");
+    let module = match document.kind() {
+        DocumentKind::Crate(module) => module,
+        kind => panic!("expected a crate, found {:?}", kind),
+    };
+    assert_eq!(module.sub_item()[0].content()[0].attribute().deprecated(), "Deprecated since 0.2");
+}
+
+// the parser has no struct pages to offer, so the document is built from JSON
+#[cfg(feature = "serde")]
+#[test]
+fn test_visitor_impls() {
+    /// Records which hook saw each impl block.
+    #[derive(Default)]
+    struct Impls<'a>(Vec<(&'static str, &'a str)>);
+
+    impl<'a> Visitor<'a> for Impls<'a> {
+        fn visit_implementation(&mut self, implementation: &'a Implementation) {
+            self.0.push(("inherent", implementation.impl_code()));
+        }
+
+        fn visit_trait_impl(&mut self, implementation: &'a Implementation) {
+            self.0.push(("trait", implementation.impl_code()));
+        }
+
+        fn visit_auto_impl(&mut self, implementation: &'a Implementation) {
+            self.0.push(("auto", implementation.impl_code()));
+        }
+
+        fn visit_blanket_impl(&mut self, implementation: &'a Implementation) {
+            self.0.push(("blanket", implementation.impl_code()));
+        }
+    }
+
+    let implementation = |code: &str| serde_json::json!({ "impl_code": code, "methods": [], "associated_types": [] });
+    let document: Document = serde_json::from_value(serde_json::json!({
+        "format_version": 1,
+        "title": "Struct paradocs_fixture::Thing",
+        "mark": { "stability": "", "features": "", "deprecated": "" },
+        "description": [],
+        "kind": { "kind": "struct", "item": {
+            "declaration": "pub struct Thing",
+            "fields": [],
+            "methods": [implementation("impl Thing")],
+            "trait_impls": [implementation("impl Clone for Thing")],
+            "auto_impls": [implementation("impl Send for Thing")],
+            "blanket_impls": [implementation("impl<T> From<T> for T")],
+        } },
+    }))
+    .unwrap();
+    let mut impls = Impls::default();
+    impls.visit_document(&document);
+    assert_eq!(
+        impls.0,
+        [
+            ("inherent", "impl Thing"),
+            ("trait", "impl Clone for Thing"),
+            ("auto", "impl Send for Thing"),
+            ("blanket", "impl<T> From<T> for T"),
+        ]
+    );
+
+    // by default every kind of impl goes to `visit_implementation`
+    let mut collector = Collector::default();
+    collector.visit_document(&document);
+    assert_eq!(collector.declarations.len(), 5);
+}
@@ -0,0 +1,159 @@
+use paradocs::element::*;
+use paradocs::{CrawlOptions, DocsClient, MemberKind, Query};
+use serde_json::{json, Value};
+
+fn client() -> DocsClient {
+    DocsClient::builder()
+        .replay(concat!(env!("CARGO_MANIFEST_DIR"), "/test/fixtures"))
+        .build()
+        .unwrap()
+}
+
+fn simple_item(declaration: &str, deprecated: &str) -> Value {
+    json!({
+        "declaration": declaration,
+        "mark": { "stability": "", "features": "", "deprecated": deprecated },
+        "description": [],
+    })
+}
+
+/// A struct with a field, an inherent impl and impls of `AsyncRead` and `!Sync`.
+fn stream() -> Document {
+    let mark = json!({ "stability": "", "features": "", "deprecated": "" });
+    serde_json::from_value(json!({
+        "format_version": 1,
+        "title": "Struct tokio::net::TcpStream",
+        "mark": mark,
+        "description": [],
+        "kind": { "kind": "struct", "item": {
+            "declaration": "pub struct TcpStream { /* fields omitted */ }",
+            "fields": [simple_item("pub peer: SocketAddr", "")],
+            "methods": [{
+                "impl_code": "impl TcpStream",
+                "methods": [
+                    simple_item("pub async fn connect<A: ToSocketAddrs>(\n    addr: A\n) -> Result<TcpStream>", ""),
+                    simple_item("pub fn from_std(stream: TcpStream) -> Result<TcpStream>", ""),
+                    simple_item("pub async fn peek(&mut self, buf: &mut [u8]) -> Result<usize>", "Deprecated since 0.2"),
+                ],
+                "associated_types": [],
+            }],
+            "trait_impls": [{
+                "impl_code": "impl<'a> AsyncRead for TcpStream",
+                "methods": [simple_item("fn poll_read(self: Pin<&mut Self>) -> Poll<Result<usize>>", "")],
+                "associated_types": [],
+            }],
+            "auto_impls": [{ "impl_code": "impl !Sync for TcpStream", "methods": [], "associated_types": [] }],
+            "blanket_impls": [{
+                "impl_code": "impl<T> From<T> for T",
+                "methods": [simple_item("fn from(t: T) -> T", "")],
+                "associated_types": [],
+            }],
+        } },
+    }))
+    .unwrap()
+}
+
+fn paths(query: &Query, document: &Document) -> Vec<String> {
+    query
+        .matches("tokio::net::TcpStream", document)
+        .iter()
+        .map(|found| found.path().to_owned())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_query_index() {
+    let index = client().crawl("paradocs_fixture", &CrawlOptions::new()).await.unwrap();
+    let found = index.query(&Query::new().kind(ItemKind::Function).deprecated(false));
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].path(), "paradocs_fixture::hello");
+    assert_eq!(found[0].document().title(), "Function paradocs_fixture::hello");
+    assert_eq!(found[0].declaration().map(String::as_str), Some("pub fn hello()"));
+
+    // re-exports are found by their other paths too
+    let found = index.query(&Query::new().path("paradocs_fixture::sub::hel*"));
+    assert_eq!(found[0].path(), "paradocs_fixture::hello");
+
+    let found = index.query(&Query::new().path("paradocs_fixture::sub"));
+    let paths: Vec<&str> = found.iter().map(|found| found.path()).collect();
+    assert_eq!(paths, ["paradocs_fixture::hello", "paradocs_fixture::sub"]);
+
+    assert!(index.query(&Query::new().declaration("async")).is_empty());
+    assert!(index.query(&Query::new().kind(ItemKind::Module).declaration("fn")).is_empty());
+}
+
+#[test]
+fn test_query_members() {
+    let stream = stream();
+    let methods = Query::new().kind(ItemKind::Struct).member(MemberKind::Method);
+    assert_eq!(
+        paths(&methods, &stream),
+        [
+            "tokio::net::TcpStream::connect",
+            "tokio::net::TcpStream::from_std",
+            "tokio::net::TcpStream::peek",
+            "tokio::net::TcpStream::poll_read",
+            "tokio::net::TcpStream::from",
+        ]
+    );
+
+    let query = methods.clone().path("tokio::net").declaration("async fn").deprecated(false);
+    let found = query.matches("tokio::net::TcpStream", &stream);
+    assert_eq!(found.len(), 1);
+    let member = found[0].member().unwrap();
+    assert_eq!(member.name(), "connect");
+    assert_eq!(member.kind(), MemberKind::Method);
+    assert_eq!(member.implementation().unwrap().impl_code(), "impl TcpStream");
+    assert_eq!(found[0].document().title(), "Struct tokio::net::TcpStream");
+
+    assert_eq!(paths(&methods.clone().path("tokio::net::TcpStream::p*").deprecated(true), &stream), ["tokio::net::TcpStream::peek"]);
+    assert_eq!(paths(&methods.clone().declaration("fn *(self"), &stream), ["tokio::net::TcpStream::poll_read"]);
+    assert!(paths(&methods.clone().kind(ItemKind::Enum).path("tokio::io"), &stream).is_empty());
+
+    let fields = Query::new().member(MemberKind::Field);
+    assert_eq!(paths(&fields, &stream), ["tokio::net::TcpStream::peer"]);
+}
+
+#[test]
+fn test_query_impls() {
+    let stream = stream();
+    let methods = Query::new().member(MemberKind::Method);
+    assert_eq!(paths(&methods.clone().in_impl("AsyncRead"), &stream), ["tokio::net::TcpStream::poll_read"]);
+    assert_eq!(paths(&methods.clone().in_impl("tokio::io::AsyncRead"), &stream), Vec::<String>::new());
+    assert_eq!(paths(&methods.clone().in_impl("From").inherent(), &stream).len(), 4);
+    assert_eq!(paths(&methods.clone().inherent().declaration("Result<TcpStream>"), &stream).len(), 2);
+
+    let structs = Query::new().kind(ItemKind::Struct);
+    assert_eq!(paths(&structs.clone().implements("AsyncRead").implements("From"), &stream), ["tokio::net::TcpStream"]);
+    assert!(paths(&structs.clone().implements("Sync"), &stream).is_empty());
+    assert!(paths(&structs.clone().implements("AsyncWrite"), &stream).is_empty());
+    assert!(paths(&structs.inherent(), &stream).is_empty());
+}
+
+#[test]
+fn test_query_impl_arrow() {
+    let mark = json!({ "stability": "", "features": "", "deprecated": "" });
+    let callback: Document = serde_json::from_value(json!({
+        "format_version": 1,
+        "title": "Struct tokio::net::TcpStream",
+        "mark": mark,
+        "description": [],
+        "kind": { "kind": "struct", "item": {
+            "declaration": "pub struct TcpStream<F>(F);",
+            "fields": [],
+            "methods": [],
+            "trait_impls": [{
+                "impl_code": "impl<F: Fn() -> R, R> Handler for TcpStream<F>",
+                "methods": [simple_item("fn handle(&self) -> R", "")],
+                "associated_types": [],
+            }],
+            "auto_impls": [],
+            "blanket_impls": [],
+        } },
+    }))
+    .unwrap();
+    let methods = Query::new().member(MemberKind::Method);
+    assert_eq!(paths(&methods.clone().in_impl("Handler"), &callback), ["tokio::net::TcpStream::handle"]);
+    assert!(paths(&methods.inherent(), &callback).is_empty());
+    assert_eq!(paths(&Query::new().implements("Handler"), &callback), ["tokio::net::TcpStream"]);
+}
@@ -0,0 +1,279 @@
+use paradocs::element::*;
+use paradocs::render::*;
+
+fn fixture_document(path: &str) -> Document {
+    let fixture = std::fs::read_to_string(format!(
+        "{}/test/fixtures/docs.rs/paradocs-fixture/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        path
+    ))
+    .unwrap();
+    Document::from_html(fixture.split_once("\n\n").unwrap().1).unwrap()
+}
+
+/// Parses a function page with the given docblock.
+fn function_document(docblock: &str) -> Document {
+    Document::from_html(&format!(
+        r#"<section id="main"><h1 class="fqn"><span class="in-band">Function demo::run</span></h1><pre class="rust fn toggle">pub fn run()</pre><div class="docblock toggle-wrapper">{}</div></section>"#,
+        docblock
+    ))
+    .unwrap()
+}
+
+#[test]
+fn test_markdown_function() {
+    let document = fixture_document("0.1.0/paradocs_fixture/fn.hello.html.get");
+    assert_eq!(
+        to_markdown(&document, &MarkdownOptions::new()),
+        "# Function paradocs_fixture::hello\n\n\
+         ```rust\npub fn hello()\n```\n\n\
+         Says *hello*.\n\n\
+         ## Examples\n\n\
+         ```rust\nparadocs_fixture::hello();\n```\n"
+    );
+}
+
+#[test]
+fn test_markdown_crate() {
+    let document = fixture_document("%2A/paradocs_fixture/index.html.get");
+    let options = MarkdownOptions::new()
+        .heading_offset(1)
+        .link_base("https://docs.rs/paradocs-fixture/0.1.0/paradocs_fixture");
+    let markdown = to_markdown(&document, &options);
+    assert!(markdown.starts_with("## Crate paradocs_fixture\n\n"));
+    assert!(markdown.contains(
+        "A crate for testing **paradocs** without a network. \
+         See [`sub`](https://docs.rs/paradocs-fixture/0.1.0/paradocs_fixture/sub/index.html).\n"
+    ));
+    assert!(markdown.contains("### Re-exports\n\n- `pub use sub::Thing;`\n"));
+    assert!(markdown.contains(
+        "### Modules\n\n- [`sub`](https://docs.rs/paradocs-fixture/0.1.0/paradocs_fixture/sub/index.html): Nested items.\n"
+    ));
+}
+
+#[test]
+fn test_markdown_sections() {
+    let document = fixture_document("%2A/paradocs_fixture/index.html.get");
+    let sections = Sections {
+        examples: true,
+        ..Sections::none()
+    };
+    let markdown = to_markdown(&document, &MarkdownOptions::new().sections(sections));
+    assert_eq!(
+        markdown,
+        "# Crate paradocs_fixture\n\n## Examples\n\n```rust\nparadocs_fixture::hello();\n```\n"
+    );
+
+    let markdown = to_markdown(&document, &MarkdownOptions::new().sections(Sections::none()));
+    assert_eq!(markdown, "# Crate paradocs_fixture\n");
+}
+
+#[test]
+fn test_markdown_escaping() {
+    let document = function_document(
+        "<p>Use <code>a`b</code> with <em> care </em>, not *stars* or [brackets] or _under_ but snake_case.</p>\
+         <ul><li>one</li><li><strong>two</strong></li></ul>\
+         <pre class=\"rust rust-example-rendered\">let s = \"```\";</pre>",
+    );
+    let markdown = to_markdown(&document, &MarkdownOptions::new().heading_offset(6));
+    assert!(markdown.starts_with("###### Function demo::run\n"));
+    assert!(markdown.contains(
+        "Use ``a`b`` with  *care* , not \\*stars\\* or \\[brackets\\] or \\_under\\_ but snake_case.\n\n\
+         - one\n- **two**\n"
+    ));
+    assert!(markdown.contains("````rust\nlet s = \"```\";\n````\n"));
+}
+
+#[test]
+fn test_plain_text_function() {
+    let document = fixture_document("0.1.0/paradocs_fixture/fn.hello.html.get");
+    assert_eq!(
+        to_plain_text(&document, &PlainTextOptions::new()),
+        "Function paradocs_fixture::hello\n\
+         ================================\n\n    \
+         pub fn hello()\n\n\
+         Says hello.\n\n\
+         Examples\n\
+         --------\n\n    \
+         paradocs_fixture::hello();\n"
+    );
+}
+
+#[test]
+fn test_plain_text_links() {
+    let document = fixture_document("%2A/paradocs_fixture/index.html.get");
+    let options = PlainTextOptions::new()
+        .width(30)
+        .link_base("https://docs.rs/paradocs-fixture/0.1.0/paradocs_fixture")
+        .sections(Sections {
+            description: true,
+            items: true,
+            ..Sections::none()
+        });
+    let text = to_plain_text(&document, &options);
+    assert!(text.contains("A crate for testing paradocs\nwithout a network. See sub[1].\n"));
+    assert!(text.contains("Modules\n-------\n\n    sub[1]: Nested items.\n"));
+    assert!(text.contains("    hello[2]: Says hello.\n"));
+    assert!(text.ends_with(
+        "\n\n[1] https://docs.rs/paradocs-fixture/0.1.0/paradocs_fixture/sub/index.html\n\
+         [2] https://docs.rs/paradocs-fixture/0.1.0/paradocs_fixture/fn.hello.html\n"
+    ));
+    for line in text.lines().filter(|line| !line.starts_with('[')) {
+        assert!(line.chars().count() <= 30, "{:?} is too long", line);
+    }
+}
+
+#[test]
+fn test_plain_text_nesting() {
+    let document = function_document(
+        "<p>Intro.</p><h1 id=\"usage\">Usage</h1><p>Call it.</p>\
+         <h2 id=\"details\">Details</h2><p>Some long words which wrap.</p>\
+         <ul><li>first item which is rather long</li><li>second</li></ul>\
+         <pre class=\"rust rust-example-rendered\">fn main() {\n    run();\n}</pre>",
+    );
+    let section = &document.description()[1];
+    assert_eq!(
+        section_to_plain_text(section, &PlainTextOptions::new().width(24)),
+        "Usage\n-----\n\n\
+         Call it.\n\n  \
+         Details\n  ~~~~~~~\n\n    \
+         Some long words\n    which wrap.\n\n      \
+         - first item which\n        is rather long\n      \
+         - second\n\n        \
+         fn main() {\n            run();\n        }\n"
+    );
+}
+
+#[test]
+fn test_embed_function() {
+    let document = fixture_document("0.1.0/paradocs_fixture/fn.hello.html.get");
+    let embed = to_embed(&document, &EmbedOptions::new());
+    assert_eq!(embed.title(), "Function paradocs_fixture::hello");
+    assert_eq!(embed.description(), "```rust\npub fn hello()\n```\n\nSays *hello*.");
+    assert_eq!(embed.fields().len(), 1);
+    assert_eq!(embed.fields()[0].name(), "Examples");
+    assert_eq!(embed.fields()[0].value(), "```rust\nparadocs_fixture::hello();\n```");
+    assert!(!embed.is_truncated());
+}
+
+#[test]
+fn test_embed_limits() {
+    let sentences = "Runs the demo once. It prints a line. ".repeat(20);
+    let code = "demo::run();\n".repeat(20);
+    let document = function_document(&format!(
+        "<p>{}</p><h1>Examples</h1><pre class=\"rust rust-example-rendered\">{}</pre><h1>Panics</h1><p>Never.</p>",
+        sentences, code
+    ));
+    let limits = EmbedLimits {
+        title: 10,
+        description: 200,
+        field_name: 20,
+        field_value: 100,
+        fields: 1,
+        total: 400,
+    };
+    let url = "https://docs.rs/demo/latest/demo/fn.run.html";
+    let embed = to_embed(&document, &EmbedOptions::new().limits(limits).url(url));
+    assert!(embed.is_truncated());
+    assert_eq!(embed.title(), "Function …");
+    assert_eq!(embed.url(), Some(url));
+
+    let description = embed.description();
+    assert!(description.chars().count() <= limits.description);
+    assert!(description.starts_with("```rust\npub fn run()\n```\n\nRuns the demo once."));
+    assert!(description.ends_with(&format!("It prints a line.\n\n[Read more]({})", url)));
+
+    assert_eq!(embed.fields().len(), 1);
+    let examples = embed.fields()[0].value();
+    assert!(examples.chars().count() <= limits.field_value);
+    assert!(examples.starts_with("```rust\ndemo::run();\n"));
+    assert!(examples.ends_with("demo::run();\n```"));
+    assert!(embed.total_chars() <= limits.total);
+}
+
+#[test]
+fn test_html_function() {
+    let document = fixture_document("0.1.0/paradocs_fixture/fn.hello.html.get");
+    assert_eq!(
+        to_html(&document, &HtmlOptions::new().heading_offset(1)),
+        "<h2>Function paradocs_fixture::hello</h2>\n\
+         <pre><code class=\"language-rust\">pub fn hello()</code></pre>\n\
+         <p>Says <em>hello</em>.</p>\n\
+         <h3>Examples</h3>\n\
+         <pre><code class=\"language-rust\">paradocs_fixture::hello();</code></pre>\n"
+    );
+}
+
+#[test]
+fn test_html_crate() {
+    let document = fixture_document("%2A/paradocs_fixture/index.html.get");
+    let options = HtmlOptions::new().link_base("https://docs.rs/paradocs-fixture/0.1.0/paradocs_fixture");
+    let html = to_html(&document, &options);
+    assert!(html.contains(
+        "<h2>Modules</h2>\n<ul>\n<li><a href=\"https://docs.rs/paradocs-fixture/0.1.0/paradocs_fixture/sub/index.html\">\
+         <code>sub</code></a>: Nested items.</li>\n</ul>\n"
+    ));
+    assert!(html.contains("<li><code>pub use sub::Thing;</code></li>"));
+}
+
+#[test]
+fn test_html_sanitizing() {
+    let document = function_document(
+        r#"<p onclick="steal()">Use <a href="javascript:alert(1)">this</a>, <a href=" JAVA&#9;SCRIPT:alert(1)">that</a> or <a href="run.html" class="x" style="color: red">the "demo" &amp; <strong>more</strong></a>.</p><script>alert(1)</script>"#,
+    );
+    let html = to_html(&document, &HtmlOptions::new().link_base("https://docs.rs/demo/latest/demo/"));
+    assert!(html.contains(
+        "<p>Use this, that or <a href=\"https://docs.rs/demo/latest/demo/run.html\">the &quot;demo&quot; &amp; </a>\
+         <a href=\"https://docs.rs/demo/latest/demo/run.html\"><strong>more</strong></a>.</p>\n"
+    ));
+    assert!(!html.contains("script"));
+    assert!(!html.contains("onclick"));
+    assert!(!html.contains("style"));
+}
+
+#[test]
+fn test_html_relative_links() {
+    let document = function_document(
+        r#"<p>See <a href="/std/option/enum.Option.html">Option</a>, <a href="../other/fn.walk.html">walk</a> and <a href="sub/index.html">sub</a>.</p>"#,
+    );
+    let html = to_html(&document, &HtmlOptions::new().link_base("https://docs.rs/demo/latest/demo/"));
+    assert!(html.contains(
+        "<p>See <a href=\"https://docs.rs/std/option/enum.Option.html\">Option</a>, \
+         <a href=\"https://docs.rs/demo/latest/other/fn.walk.html\">walk</a> and \
+         <a href=\"https://docs.rs/demo/latest/demo/sub/index.html\">sub</a>.</p>\n"
+    ));
+}
+
+#[test]
+fn test_man_function() {
+    let document = fixture_document("0.1.0/paradocs_fixture/fn.hello.html.get");
+    assert_eq!(
+        to_man(&document, &ManOptions::new()),
+        ".TH \"paradocs_fixture::hello\" \"3\" \"\" \"paradocs_fixture\" \"Rust Documentation\"\n\
+         .SH \"NAME\"\n\
+         paradocs_fixture::hello \\- Says hello\n\
+         .SH \"SYNOPSIS\"\n\
+         .nf\npub fn hello()\n.fi\n\
+         .SH \"DESCRIPTION\"\n\
+         .PP\nSays \\fIhello\\fR.\n\
+         .SS \"Examples\"\n\
+         .PP\n.nf\nparadocs_fixture::hello();\n.fi\n"
+    );
+}
+
+#[test]
+fn test_man_crate() {
+    let document = fixture_document("%2A/paradocs_fixture/index.html.get");
+    let man = to_man(&document, &ManOptions::new().section("3rs"));
+    assert!(man.starts_with(".TH \"paradocs_fixture\" \"3rs\""));
+    assert!(man.contains(".SH \"RE\\-EXPORTS\"\n.PP\n.nf\npub use sub::Thing;\n.fi\n"));
+    assert!(man.contains(".SH \"MODULES\"\n.TP\n\\fBparadocs_fixture::sub\\fR(3rs)\nNested items.\n"));
+}
+
+#[test]
+fn test_man_escaping() {
+    let document = function_document("<p>.TH looks like a request</p><pre class=\"rust rust-example-rendered\">'quoted\nlet x = -1; // \\n</pre>");
+    let man = to_man(&document, &ManOptions::new());
+    assert!(man.contains(".PP\n\\&.TH looks like a request\n"));
+    assert!(man.contains(".nf\n\\&'quoted\nlet x = \\-1; // \\en\n.fi\n"));
+}
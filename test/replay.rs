@@ -0,0 +1,403 @@
+use paradocs::*;
+use paradocs::element::*;
+
+fn client() -> DocsClient {
+    DocsClient::builder()
+        .replay(concat!(env!("CARGO_MANIFEST_DIR"), "/test/fixtures"))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_resolve_package() {
+    let client = client();
+    assert_eq!(
+        client.resolve_package("paradocs_fixture").await.as_deref(),
+        Some("paradocs-fixture")
+    );
+    assert_eq!(client.resolve_package("not_recorded").await, None);
+}
+
+#[tokio::test]
+async fn test_crate_root() {
+    let document = client().get_document("paradocs-fixture").await.unwrap();
+    assert_eq!(document.title(), "Crate paradocs_fixture");
+    let module = match document.kind() {
+        DocumentKind::Crate(module) => module,
+        kind => panic!("expected a crate, found {:?}", kind),
+    };
+    assert_eq!(module.re_exports()[0].declaration(), "pub use sub::Thing;");
+    let kinds: Vec<ItemKind> = module.sub_item().iter().map(SummarySection::item_type).collect();
+    assert_eq!(kinds, [ItemKind::Module, ItemKind::Function]);
+    assert_eq!(module.sub_item()[0].content()[0].link(), Some("sub/index.html"));
+    assert_eq!(document.description().len(), 2);
+}
+
+#[tokio::test]
+async fn test_module() {
+    let document = client().get_document("paradocs_fixture::sub").await.unwrap();
+    assert_eq!(document.title(), "Module paradocs_fixture::sub");
+}
+
+#[tokio::test]
+async fn test_function() {
+    let document = client().get_document("paradocs_fixture::hello").await.unwrap();
+    assert_eq!(document.title(), "Function paradocs_fixture::hello");
+    match document.kind() {
+        DocumentKind::Function(declaration) => assert_eq!(declaration, "pub fn hello()"),
+        kind => panic!("expected a function, found {:?}", kind),
+    }
+}
+
+#[tokio::test]
+async fn test_document_page() {
+    let client = client();
+    let (url, document) = client.get_document_page("paradocs_fixture::hello", "*").await.unwrap();
+    assert_eq!(url, "https://docs.rs/paradocs-fixture/0.1.0/paradocs_fixture/fn.hello.html");
+    assert_eq!(document.title(), "Function paradocs_fixture::hello");
+
+    // the URL is remembered along with the cached document
+    let (cached, _) = client.get_document_page("paradocs_fixture::hello", "*").await.unwrap();
+    assert_eq!(cached, url);
+}
+
+#[tokio::test]
+async fn test_missing_fixture() {
+    assert!(client().get_document("paradocs_fixture::missing").await.is_none());
+}
+
+#[tokio::test]
+async fn test_crawl() {
+    let index = client().crawl("paradocs_fixture", &CrawlOptions::new()).await.unwrap();
+    let paths: Vec<&str> = index.iter().map(|(path, _)| path).collect();
+    assert_eq!(paths, ["paradocs_fixture", "paradocs_fixture::hello", "paradocs_fixture::sub"]);
+    assert_eq!(index.canonical_path("paradocs_fixture::sub::hello"), "paradocs_fixture::hello");
+    assert_eq!(index.get("paradocs_fixture::hello").unwrap().kind(), ItemKind::Function);
+    assert_eq!(index.get("paradocs_fixture").unwrap().children().len(), 2);
+}
+
+#[tokio::test]
+async fn test_crawl_filters() {
+    let options = CrawlOptions::new().exclude("paradocs_fixture::sub");
+    let index = client().crawl("paradocs_fixture", &options).await.unwrap();
+    assert!(index.get("paradocs_fixture::sub").is_none());
+    assert!(index.get("paradocs_fixture::hello").is_some());
+
+    let options = CrawlOptions::new().max_depth(0);
+    let index = client().crawl("paradocs_fixture", &options).await.unwrap();
+    assert_eq!(index.len(), 1);
+}
+
+#[tokio::test]
+async fn test_crawl_stream_progress() {
+    use futures::StreamExt;
+    use std::sync::{Arc, Mutex};
+
+    let client = client();
+    let last_progress = Arc::new(Mutex::new(CrawlProgress::default()));
+    let progress = last_progress.clone();
+    let options = CrawlOptions::new().on_progress(move |update| *progress.lock().unwrap() = update);
+    let results: Vec<_> = client.crawl_stream("paradocs_fixture", options).collect().await;
+    assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 3);
+    assert!(matches!(results.iter().find(|result| result.is_err()), Some(Err(Error::Parse { .. }))));
+    let progress = *last_progress.lock().unwrap();
+    assert_eq!(progress, CrawlProgress { done: 4, discovered: 5, failed: 1 });
+}
+
+#[tokio::test]
+async fn test_crawl_cancelled() {
+    use futures::StreamExt;
+
+    let client = client();
+    let cancel_after_root = || {
+        let cancel = CancelToken::new();
+        let token = cancel.clone();
+        CrawlOptions::new()
+            .concurrency(1)
+            .cancel_token(cancel)
+            .on_progress(move |update| {
+                if update.done == 1 {
+                    token.cancel();
+                }
+            })
+    };
+    let results: Vec<_> = client.crawl_stream("paradocs_fixture", cancel_after_root()).collect().await;
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].as_ref().unwrap().0, "paradocs_fixture");
+    assert!(matches!(results[1], Err(Error::Cancelled)));
+
+    let result = client.crawl("paradocs_fixture", &cancel_after_root()).await;
+    assert!(matches!(result, Err(Error::Cancelled)));
+}
+
+#[tokio::test]
+async fn test_disk_cache() {
+    let directory = std::env::temp_dir().join("paradocs-replay-cache");
+    let cache = DiskCache::new(&directory).unwrap();
+    cache.clear().unwrap();
+    let client = DocsClient::builder()
+        .replay(concat!(env!("CARGO_MANIFEST_DIR"), "/test/fixtures"))
+        .disk_cache(cache)
+        .build()
+        .unwrap();
+    client.crawl("paradocs_fixture", &CrawlOptions::new()).await.unwrap();
+    assert!(client.cache().unwrap().size().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn test_disk_cache_eviction() {
+    let directory = std::env::temp_dir().join("paradocs-replay-eviction");
+    DiskCache::new(&directory).unwrap().clear().unwrap();
+    let cache = DiskCache::new(&directory).unwrap().max_size(1500);
+    let client = DocsClient::builder()
+        .replay(concat!(env!("CARGO_MANIFEST_DIR"), "/test/fixtures"))
+        .disk_cache(cache)
+        .build()
+        .unwrap();
+    client.crawl("paradocs_fixture", &CrawlOptions::new()).await.unwrap();
+    let cache = client.cache().unwrap();
+    assert!(cache.size().unwrap() <= 1500);
+    assert!(!cache.is_empty().unwrap());
+
+    // a fresh handle counts the same pages from the directory
+    let reopened = DiskCache::new(&directory).unwrap();
+    assert_eq!(reopened.size().unwrap(), cache.size().unwrap());
+    assert_eq!(reopened.len().unwrap(), cache.len().unwrap());
+}
+
+/// Copies the recorded fixtures, so that a test can change them.
+fn copy_fixtures(name: &str) -> std::path::PathBuf {
+    fn copy(from: &std::path::Path, to: &std::path::Path) {
+        std::fs::create_dir_all(to).unwrap();
+        for entry in std::fs::read_dir(from).unwrap() {
+            let entry = entry.unwrap();
+            if entry.file_type().unwrap().is_dir() {
+                copy(&entry.path(), &to.join(entry.file_name()));
+            } else {
+                std::fs::copy(entry.path(), to.join(entry.file_name())).unwrap();
+            }
+        }
+    }
+    let directory = std::env::temp_dir().join(name);
+    let _ = std::fs::remove_dir_all(&directory);
+    copy(std::path::Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/test/fixtures")), &directory);
+    directory
+}
+
+/// Copies the recorded fixtures, answering the `*` page of `hello` with `response` instead.
+fn fixtures_with_hello(name: &str, response: &str) -> std::path::PathBuf {
+    let directory = copy_fixtures(name);
+    let page = directory.join("docs.rs/paradocs-fixture/%2A/paradocs_fixture/fn.hello.html.get");
+    std::fs::write(page, response).unwrap();
+    directory
+}
+
+#[tokio::test]
+async fn test_disk_cache_revalidates_latest() {
+    let cache_directory = std::env::temp_dir().join("paradocs-replay-revalidate");
+    DiskCache::new(&cache_directory).unwrap().clear().unwrap();
+    let summary = |fixtures: std::path::PathBuf| {
+        let cache_directory = cache_directory.clone();
+        async move {
+            let client = DocsClient::builder()
+                .replay(fixtures)
+                .disk_cache(DiskCache::new(cache_directory).unwrap())
+                .build()
+                .unwrap();
+            let document = client.get_document("paradocs_fixture::hello").await.unwrap();
+            match &document.description()[0].content()[0] {
+                Paragraph::Text(text) => text.to_string(),
+                paragraph => panic!("expected text, found {:?}", paragraph),
+            }
+        }
+    };
+    let page = |status: u16, body: &str| {
+        format!(
+            "{} https://docs.rs/paradocs-fixture/0.1.0/paradocs_fixture/fn.hello.html\netag: \"{}\"\n\n{}",
+            status, status, body
+        )
+    };
+    let hello = |summary: &str| {
+        format!(
+            "<html><body><section id=\"main\"><h1 class=\"fqn\"><span>Function paradocs_fixture::hello</span></h1>\
+             <pre class=\"rust fn toggle\">pub fn hello()</pre><div class=\"docblock toggle\"><p>{}</p></div>\
+             </section></body></html>",
+            summary
+        )
+    };
+
+    assert_eq!(summary(fixtures_with_hello("paradocs-fixtures-200", &page(200, &hello("Says hello.")))).await, "Says hello.");
+    // unchanged pages keep their cached body
+    assert_eq!(summary(fixtures_with_hello("paradocs-fixtures-304", &page(304, ""))).await, "Says hello.");
+    // a new latest release replaces it, although the cached page was of a concrete version
+    let fixtures = fixtures_with_hello("paradocs-fixtures-new", &page(200, &hello("Says goodbye.")));
+    assert_eq!(summary(fixtures).await, "Says goodbye.");
+}
+
+#[tokio::test]
+async fn test_disk_cache_under_base_path() {
+    // the recorded fixtures, served from `https://mirror.example/docs` instead
+    let fixtures = std::env::temp_dir().join("paradocs-fixtures-mirror");
+    let _ = std::fs::remove_dir_all(&fixtures);
+    let recorded = std::path::Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/test/fixtures/docs.rs"));
+    let mirror = fixtures.join("mirror.example/docs");
+    let mut pending = vec![recorded.to_path_buf()];
+    while let Some(directory) = pending.pop() {
+        for entry in std::fs::read_dir(directory).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            let target = mirror.join(path.strip_prefix(recorded).unwrap());
+            std::fs::create_dir_all(target.parent().unwrap()).unwrap();
+            let content = std::fs::read_to_string(&path).unwrap();
+            std::fs::write(target, content.replacen("https://docs.rs/", "https://mirror.example/docs/", 1)).unwrap();
+        }
+    }
+
+    let cache_directory = std::env::temp_dir().join("paradocs-replay-mirror-cache");
+    DiskCache::new(&cache_directory).unwrap().clear().unwrap();
+    let client = || {
+        DocsClient::builder()
+            .base_url("https://mirror.example/docs/")
+            .replay(&fixtures)
+            .disk_cache(DiskCache::new(&cache_directory).unwrap())
+            .build()
+            .unwrap()
+    };
+    let document = client().get_package_document("paradocs-fixture", "0.1.0", "paradocs_fixture").await;
+    assert_eq!(document.unwrap().title(), "Crate paradocs_fixture");
+
+    // pages of an exact version are served from the cache without asking the mirror again
+    std::fs::remove_file(mirror.join("paradocs-fixture/0.1.0/paradocs_fixture/index.html.get")).unwrap();
+    let document = client().get_package_document("paradocs-fixture", "0.1.0", "paradocs_fixture").await;
+    assert_eq!(document.unwrap().title(), "Crate paradocs_fixture");
+}
+
+#[tokio::test]
+async fn test_memory_cache_resolves_packages_once() {
+    let fixtures = copy_fixtures("paradocs-fixtures-packages");
+    let client = DocsClient::builder().replay(&fixtures).build().unwrap();
+    assert!(client.get_document("paradocs_fixture::hello").await.is_some());
+
+    // later lookups no longer probe docs.rs for the package
+    std::fs::remove_file(fixtures.join("docs.rs/paradocs_fixture.head")).unwrap();
+    std::fs::remove_file(fixtures.join("docs.rs/paradocs-fixture.head")).unwrap();
+    assert_eq!(client.resolve_package("paradocs_fixture").await.as_deref(), Some("paradocs-fixture"));
+    assert!(client.get_document("paradocs_fixture").await.is_some());
+}
+
+#[tokio::test]
+async fn test_memory_cache_latest_ttl() {
+    let client = client();
+    let first = client.get_document("paradocs_fixture::hello").await.unwrap();
+    let second = client.get_document("paradocs_fixture::hello").await.unwrap();
+    assert!(std::sync::Arc::ptr_eq(&first, &second));
+
+    let client = DocsClient::builder()
+        .replay(concat!(env!("CARGO_MANIFEST_DIR"), "/test/fixtures"))
+        .memory_cache(MemoryCache::default().latest_ttl(std::time::Duration::ZERO))
+        .build()
+        .unwrap();
+    let first = client.get_document("paradocs_fixture::hello").await.unwrap();
+    let second = client.get_document("paradocs_fixture::hello").await.unwrap();
+    assert!(!std::sync::Arc::ptr_eq(&first, &second));
+}
+
+#[tokio::test]
+async fn test_retry_after() {
+    for (name, retry_after) in [
+        ("paradocs-fixtures-retry-huge", "18446744073709551615"),
+        ("paradocs-fixtures-retry-past", "Wed, 21 Oct 2015 07:28:00 GMT"),
+    ] {
+        let fixtures = copy_fixtures(name);
+        let response = format!("429 https://docs.rs/paradocs_fixture\nretry-after: {}\n\n", retry_after);
+        std::fs::write(fixtures.join("docs.rs/paradocs_fixture.head"), response).unwrap();
+        let client = DocsClient::builder()
+            .replay(&fixtures)
+            .retries(1, std::time::Duration::from_millis(1))
+            .build()
+            .unwrap();
+        // the throttled spelling is given up on, and the other one still resolves
+        let start = std::time::Instant::now();
+        assert_eq!(client.resolve_package("paradocs_fixture").await.as_deref(), Some("paradocs-fixture"));
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+    }
+}
+
+#[tokio::test]
+async fn test_item_index() {
+    let index = client().item_index("paradocs-fixture").await.unwrap();
+    assert_eq!(index.crate_name(), "paradocs_fixture");
+    assert_eq!(index.root_url(), "https://docs.rs/paradocs-fixture/0.1.0/paradocs_fixture/index.html");
+    let paths: Vec<_> = index.entries().iter().map(|entry| (entry.path(), entry.kind())).collect();
+    assert_eq!(
+        paths,
+        [("paradocs_fixture::sub::Thing", ItemKind::Struct), ("paradocs_fixture::hello", ItemKind::Function)]
+    );
+    assert_eq!(
+        index.get("paradocs_fixture::hello").unwrap().url(),
+        "https://docs.rs/paradocs-fixture/0.1.0/paradocs_fixture/fn.hello.html"
+    );
+}
+
+#[tokio::test]
+async fn test_search() {
+    let client = client();
+    let index = client.item_index("paradocs_fixture").await.unwrap();
+    let names: Vec<_> = index.search("h").iter().map(|entry| entry.name()).collect();
+    assert_eq!(names, ["hello", "Thing"]);
+    let names: Vec<_> = index.search("SUB::thing").iter().map(|entry| entry.name()).collect();
+    assert_eq!(names, ["Thing"]);
+    assert!(index.search("  ").is_empty());
+
+    let found = client.search("paradocs_fixture", "hello").await.unwrap();
+    assert_eq!(found[0].path(), "paradocs_fixture::hello");
+    assert!(matches!(client.search("not_recorded", "x").await, Err(Error::PackageNotFound(_))));
+}
+
+#[tokio::test]
+async fn test_document_url() {
+    let client = client();
+    let (url, document) = client
+        .get_document_url("https://docs.rs/paradocs-fixture/0.1.0/paradocs_fixture/index.html")
+        .await
+        .unwrap();
+    assert_eq!(url, "https://docs.rs/paradocs-fixture/0.1.0/paradocs_fixture/index.html");
+    assert_eq!(document.title(), "Crate paradocs_fixture");
+}
+
+#[tokio::test]
+async fn test_symbols() {
+    use paradocs::render::{symbols, to_ctags};
+
+    let index = client().crawl("paradocs_fixture", &CrawlOptions::new()).await.unwrap();
+    let symbols = symbols(&index);
+    let paths: Vec<&str> = symbols.iter().map(|symbol| symbol.path()).collect();
+    assert_eq!(
+        paths,
+        [
+            "paradocs_fixture",
+            "paradocs_fixture::hello",
+            "paradocs_fixture::sub",
+            "paradocs_fixture::sub::Thing",
+            "paradocs_fixture::sub::hello",
+        ]
+    );
+    let thing = &symbols[3];
+    assert_eq!(thing.kind(), ItemKind::Struct);
+    assert_eq!(thing.declaration(), None);
+    assert_eq!(thing.summary(), "A thing.");
+    assert_eq!(thing.url(), "https://docs.rs/paradocs-fixture/0.1.0/paradocs_fixture/sub/struct.Thing.html");
+    assert_eq!(symbols[1].declaration(), Some("pub fn hello()"));
+
+    let tags = to_ctags(&symbols);
+    assert!(tags.starts_with("!_TAG_FILE_FORMAT\t2\t/extended format/\n"));
+    assert!(tags.contains(
+        "\nhello\thttps://docs.rs/paradocs-fixture/0.1.0/paradocs_fixture/fn.hello.html\t1;\"\
+         \tkind:function\tpath:paradocs_fixture::hello\tsignature:pub fn hello()\n"
+    ));
+    let names: Vec<&str> = tags.lines().skip(3).map(|line| line.split('\t').next().unwrap()).collect();
+    assert_eq!(names, ["Thing", "hello", "hello", "paradocs_fixture", "sub"]);
+}
@@ -0,0 +1,176 @@
+use paradocs::element::*;
+use serde_json::{json, Value};
+
+fn fixture_document(path: &str) -> Document {
+    let fixture = std::fs::read_to_string(format!(
+        "{}/test/fixtures/docs.rs/paradocs-fixture/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        path
+    ))
+    .unwrap();
+    Document::from_html(fixture.split_once("\n\n").unwrap().1).unwrap()
+}
+
+#[test]
+fn test_function_shape() {
+    let document = fixture_document("0.1.0/paradocs_fixture/fn.hello.html.get");
+    let value = serde_json::to_value(&document).unwrap();
+    assert_eq!(value["title"], "Function paradocs_fixture::hello");
+    assert_eq!(value["kind"], json!({ "kind": "function", "item": "pub fn hello()" }));
+    assert_eq!(value["mark"], json!({ "stability": "", "features": "", "deprecated": "" }));
+    assert_eq!(
+        value["description"][0]["content"][0],
+        json!({ "type": "text", "content": { "components": [
+            { "text": "Says ", "code": false, "italic": false, "bold": false },
+            { "text": "hello", "code": false, "italic": true, "bold": false },
+            { "text": ".", "code": false, "italic": false, "bold": false },
+        ] } })
+    );
+    assert_eq!(value["description"][1]["content"][0]["type"], "code");
+}
+
+#[test]
+fn test_crate_shape() {
+    let document = fixture_document("%2A/paradocs_fixture/index.html.get");
+    let value = serde_json::to_value(&document).unwrap();
+    assert_eq!(value["kind"]["kind"], "crate");
+    let module = &value["kind"]["item"];
+    assert_eq!(module["re_exports"], json!(["pub use sub::Thing;"]));
+    assert_eq!(module["sub_items"][0]["item_type"], "module");
+    assert_eq!(module["sub_items"][0]["content"][0]["name"], "sub");
+    assert_eq!(module["sub_items"][0]["content"][0]["link"], "sub/index.html");
+}
+
+#[test]
+fn test_round_trip() {
+    let document = fixture_document("%2A/paradocs_fixture/index.html.get");
+    let json = serde_json::to_string(&document).unwrap();
+    let restored: Document = serde_json::from_str(&json).unwrap();
+    assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+}
+
+#[test]
+fn test_format_version() {
+    let document = fixture_document("0.1.0/paradocs_fixture/fn.hello.html.get");
+    let value = serde_json::to_value(&document).unwrap();
+    assert_eq!(value["format_version"], FORMAT_VERSION);
+
+    let mut future = value.clone();
+    future["format_version"] = json!(FORMAT_VERSION + 1);
+    assert!(serde_json::from_value::<Document>(future.clone()).is_err());
+    assert!(matches!(
+        migrate(future),
+        Err(MigrationError::UnsupportedVersion(version)) if version == u64::from(FORMAT_VERSION) + 1
+    ));
+}
+
+#[test]
+fn test_migrate_unversioned() {
+    let document = fixture_document("%2A/paradocs_fixture/index.html.get");
+    let current = serde_json::to_value(&document).unwrap();
+    let mut legacy = current.clone();
+    legacy.as_object_mut().unwrap().remove("format_version");
+
+    assert!(serde_json::from_value::<Document>(legacy.clone()).is_err());
+    let migrated = migrate_str(&legacy.to_string()).unwrap();
+    assert_eq!(serde_json::to_value(&migrated).unwrap(), current);
+}
+
+/// Checks `value` against `schema`, supporting the draft-07 keywords used by [`DOCUMENT_SCHEMA`].
+fn validate(root: &Value, schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    if let Some(reference) = schema["$ref"].as_str() {
+        let name = reference.strip_prefix("#/definitions/").unwrap();
+        let definition = root["definitions"].get(name).ok_or_else(|| format!("undefined reference {}", name))?;
+        return validate(root, definition, value, path);
+    }
+    if let Some(options) = schema["oneOf"].as_array() {
+        let matching = options.iter().filter(|option| validate(root, option, value, path).is_ok()).count();
+        if matching != 1 {
+            return Err(format!("{}: {} of oneOf match", path, matching));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if value != expected {
+            return Err(format!("{}: expected {}", path, expected));
+        }
+    }
+    if let Some(allowed) = schema["enum"].as_array() {
+        if !allowed.contains(value) {
+            return Err(format!("{}: {} is not allowed", path, value));
+        }
+    }
+    let matches_type = match schema["type"].as_str() {
+        None => true,
+        Some("null") => value.is_null(),
+        Some("boolean") => value.is_boolean(),
+        Some("string") => value.is_string(),
+        Some("array") => value.is_array(),
+        Some("object") => value.is_object(),
+        Some(other) => panic!("unsupported type {}", other),
+    };
+    if !matches_type {
+        return Err(format!("{}: expected {}", path, schema["type"]));
+    }
+    if let (Some(object), Some(properties)) = (value.as_object(), schema["properties"].as_object()) {
+        for key in schema["required"].as_array().into_iter().flatten() {
+            if !object.contains_key(key.as_str().unwrap()) {
+                return Err(format!("{}: missing {}", path, key));
+            }
+        }
+        for (key, field) in object {
+            match properties.get(key) {
+                Some(property) => validate(root, property, field, &format!("{}.{}", path, key))?,
+                None if schema["additionalProperties"] == false => {
+                    return Err(format!("{}: unexpected {}", path, key))
+                }
+                None => {}
+            }
+        }
+    }
+    if let (Some(array), Some(items)) = (value.as_array(), schema.get("items")) {
+        for (index, item) in array.iter().enumerate() {
+            validate(root, items, item, &format!("{}[{}]", path, index))?;
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_schema() {
+    let schema: Value = serde_json::from_str(DOCUMENT_SCHEMA).unwrap();
+    assert_eq!(schema["properties"]["format_version"]["const"], FORMAT_VERSION);
+
+    let mark = json!({ "stability": "", "features": "", "deprecated": "" });
+    let implementation = json!({ "impl_code": "impl Send for Thing", "methods": [], "associated_types": [] });
+    let thing: Document = serde_json::from_value(json!({
+        "format_version": 1,
+        "title": "Struct paradocs_fixture::Thing",
+        "mark": mark,
+        "description": [],
+        "kind": { "kind": "struct", "item": {
+            "declaration": "pub struct Thing { pub size: usize }",
+            "fields": [{ "declaration": "size: usize", "mark": mark, "description": [] }],
+            "methods": [],
+            "trait_impls": [],
+            "auto_impls": [implementation],
+            "blanket_impls": [],
+        } },
+    }))
+    .unwrap();
+    let documents = vec![
+        fixture_document("%2A/paradocs_fixture/index.html.get"),
+        fixture_document("0.1.0/paradocs_fixture/fn.hello.html.get"),
+        fixture_document("0.1.0/paradocs_fixture/sub/index.html.get"),
+        thing,
+    ];
+    for document in &documents {
+        let value = serde_json::to_value(document).unwrap();
+        if let Err(error) = validate(&schema, &schema, &value, "$") {
+            panic!("{} does not match the schema: {}", document.title(), error);
+        }
+    }
+
+    let mut invalid = serde_json::to_value(&documents[1]).unwrap();
+    invalid["kind"]["kind"] = json!("struct");
+    assert!(validate(&schema, &schema, &invalid, "$").is_err());
+}
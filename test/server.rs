@@ -0,0 +1,119 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+use serde_json::Value;
+
+/// A server on a free port, killed when dropped.
+struct Server {
+    child: Child,
+    address: String,
+}
+
+impl Server {
+    fn start() -> Self {
+        Self::spawn(&["--bind", "127.0.0.1:0"], Some(concat!(env!("CARGO_MANIFEST_DIR"), "/test/fixtures")))
+    }
+
+    fn spawn(args: &[&str], replay: Option<&str>) -> Self {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_paradocs-server"));
+        command.args(args).env_remove("PARADOCS_RECORD").env_remove("PARADOCS_REPLAY");
+        if let Some(directory) = replay {
+            command.env("PARADOCS_REPLAY", directory);
+        }
+        let mut child = command.stdout(Stdio::piped()).spawn().unwrap();
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap()).read_line(&mut line).unwrap();
+        let address = line.trim().strip_prefix("listening on http://").unwrap().to_owned();
+        Server { child, address }
+    }
+
+    fn get(&self, path: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(&self.address).unwrap();
+        write!(stream, "GET {} HTTP/1.0\r\nHost: {}\r\n\r\n", path, self.address).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        assert!(head.to_lowercase().contains("content-type: application/json"));
+        (status, serde_json::from_str(body).unwrap())
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn test_items() {
+    let server = Server::start();
+    let (status, document) = server.get("/crates/paradocs_fixture/latest/items/hello");
+    assert_eq!(status, 200);
+    assert_eq!(document["title"], "Function paradocs_fixture::hello");
+    assert_eq!(document["format_version"], 1);
+
+    let (status, document) = server.get("/crates/paradocs-fixture/latest/items");
+    assert_eq!(status, 200);
+    assert_eq!(document["title"], "Crate paradocs_fixture");
+
+    let (status, error) = server.get("/crates/paradocs_fixture/latest/items/missing");
+    assert_eq!(status, 404);
+    assert_eq!(error["error"], "no documentation found for `paradocs_fixture::missing`");
+
+    let (status, error) = server.get("/crates/no_such_crate/latest/items");
+    assert_eq!(status, 404);
+    assert_eq!(error["error"], "no documentation found for `no_such_crate`");
+}
+
+#[test]
+fn test_upstream_failure() {
+    let server = Server::start();
+    // nothing was recorded for this version, as if docs.rs could not be reached
+    let (status, error) = server.get("/crates/paradocs_fixture/0.9.0/items/hello");
+    assert_eq!(status, 502);
+    assert!(error["error"].as_str().unwrap().starts_with("no recorded response for"));
+
+    let (status, _) = server.get("/crates/paradocs_fixture/0.9.0/modules");
+    assert_eq!(status, 502);
+}
+
+#[test]
+fn test_upstream_timeout() {
+    // accepts connections but never answers them
+    let upstream = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", upstream.local_addr().unwrap());
+    let server = Server::spawn(&["--bind", "127.0.0.1:0", "--base-url", &base_url, "--timeout", "0.2"], None);
+    let (status, _) = server.get("/crates/tokio/latest/items");
+    assert_eq!(status, 504);
+}
+
+#[test]
+fn test_modules() {
+    let server = Server::start();
+    let (status, module) = server.get("/crates/paradocs_fixture/latest/modules/");
+    assert_eq!(status, 200);
+    assert_eq!(module["path"], "paradocs_fixture");
+    assert_eq!(module["re_exports"][0], "pub use sub::Thing;");
+    assert_eq!(module["items"][0]["path"], "paradocs_fixture::sub");
+    assert_eq!(module["items"][0]["kind"], "module");
+    assert_eq!(module["items"][0]["summary"], "Nested items.");
+
+    let (status, _) = server.get("/crates/paradocs_fixture/latest/modules/hello");
+    assert_eq!(status, 404);
+}
+
+#[test]
+fn test_search() {
+    let server = Server::start();
+    let (status, results) = server.get("/crates/paradocs_fixture/latest/search?q=Thi");
+    assert_eq!(status, 200);
+    assert_eq!(results[0]["path"], "paradocs_fixture::sub::Thing");
+    assert_eq!(results[0]["kind"], "struct");
+
+    let (status, error) = server.get("/crates/paradocs_fixture/latest/search");
+    assert_eq!(status, 400);
+    assert_eq!(error["error"], "missing query parameter `q`");
+    assert_eq!(server.get("/elsewhere").0, 404);
+}
@@ -1,57 +1,219 @@
+//! Tests against the live docs.rs, ignored by default.
+//!
+//! Run them with `cargo test --test tokio -- --ignored`. Setting
+//! `PARADOCS_RECORD` saves the pages they fetch, and `PARADOCS_REPLAY`
+//! answers them from such a recording instead of the network. The offline
+//! tests are in `replay.rs`.
+
 use paradocs::*;
 
+/// Talks to docs.rs, or records to / replays from the directory in
+/// `PARADOCS_RECORD` / `PARADOCS_REPLAY` to freeze the pages under test.
+fn builder() -> DocsClientBuilder {
+    let builder = DocsClient::builder();
+    if let Some(directory) = std::env::var_os("PARADOCS_RECORD") {
+        builder.record(directory)
+    } else if let Some(directory) = std::env::var_os("PARADOCS_REPLAY") {
+        builder.replay(directory)
+    } else {
+        builder
+    }
+}
+
+fn client() -> DocsClient {
+    builder().build().unwrap()
+}
+
 #[tokio::test]
+#[ignore = "talks to docs.rs"]
 async fn test_crate_root() {
-    let client = DocsClient::default();
-    let document = client.get_document("tokio", "tokio").await.unwrap();
-    eprintln!("{:#?}", document);
+    let client = client();
+    let document = client.get_document("tokio").await.unwrap();
+    assert!(document.title().ends_with("tokio"));
 }
 
 #[tokio::test]
+#[ignore = "talks to docs.rs"]
 async fn test_module() {
-    let client = DocsClient::default();
-    let document = client.get_document("tokio", "tokio::fs").await.unwrap();
-    eprintln!("{:#?}", document);
+    let client = client();
+    let document = client.get_document("tokio::fs").await.unwrap();
+    assert!(document.title().ends_with("tokio::fs"));
 }
 
 #[tokio::test]
+#[ignore = "talks to docs.rs"]
 async fn test_struct() {
-    let client = DocsClient::default();
-    let document = client.get_document("tokio", "tokio::net::TcpStream").await.unwrap();
-    eprintln!("{:#?}", document);
+    let client = client();
+    let document = client.get_document("tokio::net::TcpStream").await.unwrap();
+    assert!(document.title().ends_with("tokio::net::TcpStream"));
 }
 
 #[tokio::test]
+#[ignore = "talks to docs.rs"]
 async fn test_trait() {
-    let client = DocsClient::default();
-    let document = client.get_document("tokio", "tokio::io::AsyncReadExt").await.unwrap();
-    eprintln!("{:#?}", document);
+    let client = client();
+    let document = client.get_document("tokio::io::AsyncReadExt").await.unwrap();
+    assert!(document.title().ends_with("tokio::io::AsyncReadExt"));
 }
 
 #[tokio::test]
+#[ignore = "talks to docs.rs"]
 async fn test_enum() {
-    let client = DocsClient::default();
-    let document = client.get_document("tokio", "tokio::io::ErrorKind").await.unwrap();
-    eprintln!("{:#?}", document);
+    let client = client();
+    let document = client.get_document("tokio::io::ErrorKind").await.unwrap();
+    assert!(document.title().ends_with("tokio::io::ErrorKind"));
 }
 
 #[tokio::test]
+#[ignore = "talks to docs.rs"]
 async fn test_function() {
-    let client = DocsClient::default();
-    let document = client.get_document("tokio", "tokio::spawn").await.unwrap();
-    eprintln!("{:#?}", document);
+    let client = client();
+    let document = client.get_document("tokio::spawn").await.unwrap();
+    assert!(document.title().ends_with("tokio::spawn"));
 }
 
 #[tokio::test]
+#[ignore = "talks to docs.rs"]
 async fn test_macro() {
-    let client = DocsClient::default();
-    let document = client.get_document("tokio", "tokio::task_local").await.unwrap();
-    eprintln!("{:#?}", document);
+    let client = client();
+    let document = client.get_document("tokio::task_local").await.unwrap();
+    assert!(document.title().ends_with("tokio::task_local"));
 }
 
 #[tokio::test]
+#[ignore = "talks to docs.rs"]
 async fn test_attribute() {
-    let client = DocsClient::default();
-    let document = client.get_document("tokio", "tokio::main").await.unwrap();
-    eprintln!("{:#?}", document);
+    let client = client();
+    let document = client.get_document("tokio::main").await.unwrap();
+    assert!(document.title().ends_with("tokio::main"));
+}
+
+#[tokio::test]
+#[ignore = "talks to docs.rs"]
+async fn test_package_document() {
+    let client = client();
+    let document = client.get_package_document("tokio", "*", "tokio::sync").await.unwrap();
+    assert_eq!(document.title(), "Module tokio::sync");
+    assert!(matches!(document.kind(), element::DocumentKind::Module(_)));
+}
+
+#[tokio::test]
+#[ignore = "talks to docs.rs"]
+async fn test_resolve_package() {
+    let client = client();
+    assert_eq!(client.resolve_package("serde_json").await.as_deref(), Some("serde_json"));
+    assert_eq!(client.resolve_package("async_std").await.as_deref(), Some("async-std"));
+}
+
+#[tokio::test]
+#[ignore = "talks to docs.rs"]
+async fn test_crawl() {
+    let client = client();
+    let options = CrawlOptions::new().include("tokio::fs").max_depth(2);
+    let index = client.crawl("tokio", &options).await.unwrap();
+    assert_eq!(index.root(), "tokio");
+    assert!(index.get("tokio::fs").is_some());
+    assert!(index.iter().all(|(path, _)| path == "tokio" || path.starts_with("tokio::fs")));
+}
+
+#[tokio::test]
+#[ignore = "talks to docs.rs"]
+async fn test_crawl_stream() {
+    use futures::StreamExt;
+    use std::sync::{Arc, Mutex};
+
+    let client = client();
+    let cancel = CancelToken::new();
+    let last_progress = Arc::new(Mutex::new(CrawlProgress::default()));
+    let progress = last_progress.clone();
+    let options = CrawlOptions::new()
+        .concurrency(2)
+        .cancel_token(cancel.clone())
+        .on_progress(move |update| *progress.lock().unwrap() = update);
+    let mut stream = Box::pin(client.crawl_stream("tokio", options));
+    let mut fetched = 0;
+    while let Some(item) = stream.next().await {
+        if cancel.is_cancelled() {
+            assert!(matches!(item, Err(Error::Cancelled)));
+            continue;
+        }
+        item.unwrap();
+        fetched += 1;
+        if fetched == 5 {
+            cancel.cancel();
+        }
+    }
+    assert_eq!(fetched, 5);
+    let progress = *last_progress.lock().unwrap();
+    assert!(progress.done >= 5);
+    assert!(progress.discovered >= progress.done);
+}
+
+#[tokio::test]
+#[ignore = "talks to docs.rs"]
+async fn test_disk_cache() {
+    let directory = std::env::temp_dir().join("paradocs-test-cache");
+    let cache = DiskCache::new(&directory).unwrap().max_size(10 * 1024 * 1024);
+    cache.clear().unwrap();
+    let client = builder()
+        .disk_cache(cache)
+        .memory_cache(MemoryCache::new(0))
+        .build()
+        .unwrap();
+    let document = client.get_package_document("tokio", "*", "tokio::fs").await.unwrap();
+    assert!(client.cache().unwrap().size().unwrap() > 0);
+    let cached = client.get_package_document("tokio", "*", "tokio::fs").await.unwrap();
+    assert_eq!(document.title(), cached.title());
+}
+
+#[tokio::test]
+#[ignore = "talks to docs.rs"]
+async fn test_memory_cache() {
+    let client = builder().memory_cache(MemoryCache::new(8)).build().unwrap();
+    let (first, second) = futures::join!(
+        client.get_document("tokio::fs"),
+        client.get_document("tokio::fs"),
+    );
+    assert!(std::sync::Arc::ptr_eq(&first.unwrap(), &second.unwrap()));
+    // the document, and the package providing the crate
+    assert_eq!(client.memory_cache().len(), 2);
+}
+
+#[tokio::test]
+#[ignore = "talks to docs.rs"]
+async fn test_builder() {
+    let client = builder()
+        .base_url("https://docs.rs/")
+        .timeout(std::time::Duration::from_secs(30))
+        .retries(3, std::time::Duration::from_millis(200))
+        .user_agent("paradocs-test")
+        .build()
+        .unwrap();
+    let document = client.get_document("tokio::fs").await.unwrap();
+    assert_eq!(document.title(), "Module tokio::fs");
+}
+
+#[tokio::test]
+#[ignore = "talks to docs.rs"]
+async fn test_rate_limit() {
+    // replayed requests are never held back, so this one always talks to docs.rs
+    let client = DocsClient::builder()
+        .rate_limit(2.0, 1)
+        .max_connections(1)
+        .memory_cache(MemoryCache::new(0))
+        .build()
+        .unwrap();
+    let start = std::time::Instant::now();
+    for _ in 0..3 {
+        client.get_package_document("tokio", "*", "tokio").await.unwrap();
+    }
+    assert!(start.elapsed() >= std::time::Duration::from_secs(1));
+}
+
+#[test]
+#[ignore = "talks to docs.rs"]
+fn test_without_tokio() {
+    let client = client();
+    let document = futures::executor::block_on(client.get_document("tokio::fs")).unwrap();
+    assert!(document.title().ends_with("tokio::fs"));
 }